@@ -1,82 +1,82 @@
 use crate::constants::{self, *};
-use ray::{Texture2D, Vector2};
+use crate::laser::LaserKind;
+use crate::resources::{Resources, ALIENS_TEXTURE};
+use ray::{Rectangle, Vector2};
 use raylib_ffi as ray;
 
+#[derive(Clone)]
 pub struct Alien {
-    texture: [Texture2D; 3],
     kind: usize,
+    // column of the formation the alien started in
+    column: usize,
     position: ray::Vector2,
     active: bool,
     score: usize,
+    // animation frame, toggled every time the alien moves
+    frame: usize,
 }
 
 impl Alien {
-    pub fn new(kind: usize, position: ray::Vector2) -> Box<Alien> {
-        // Alien1
-        let alien1_data = include_bytes!("../assets/images/alien_1.png");
-        let texture1 = unsafe {
-            let alien1_image = ray::LoadImageFromMemory(
-                ray::rl_str!(".png"),
-                alien1_data.as_ptr(),
-                alien1_data.len() as i32,
-            );
-            ray::LoadTextureFromImage(alien1_image)
-        };
-
-        // Alien2
-        let alien2_data = include_bytes!("../assets/images/alien_2.png");
-        let texture2 = unsafe {
-            let alien2_image = ray::LoadImageFromMemory(
-                ray::rl_str!(".png"),
-                alien2_data.as_ptr(),
-                alien2_data.len() as i32,
-            );
-            ray::LoadTextureFromImage(alien2_image)
-        };
-
-        // Alien3
-        let alien3_data = include_bytes!("../assets/images/alien_3.png");
-        let texture3 = unsafe {
-            let alien3_image = ray::LoadImageFromMemory(
-                ray::rl_str!(".png"),
-                alien3_data.as_ptr(),
-                alien3_data.len() as i32,
-            );
-            ray::LoadTextureFromImage(alien3_image)
-        };
-
-        Box::new(Alien {
-            texture: [texture1, texture2, texture3],
+    pub fn new(kind: usize, column: usize, position: ray::Vector2, score: usize) -> Alien {
+        Alien {
             kind,
+            column,
             position,
             active: true,
-            score: ALIEN_SCORES[kind],
-        })
+            score,
+            frame: 0,
+        }
+    }
+
+    /// Where a frame of an alien type is in the sprite sheet
+    pub fn get_frame_rect(kind: usize, frame: usize) -> Rectangle {
+        let size = ALIEN_SPRITE_SIZES[kind];
+        Rectangle {
+            x: frame as f32 * ALIEN_SHEET_CELL.x,
+            y: kind as f32 * ALIEN_SHEET_CELL.y,
+            width: size.x,
+            height: size.y,
+        }
+    }
+
+    pub fn get_column(&self) -> usize {
+        self.column
     }
 
     pub fn get_score(&self) -> usize {
         self.score
     }
 
-    pub fn has_overflowed_right(&self) -> bool {
-        self.position.x as i32 + self.texture[self.kind].width > WINDOW_WIDTH - OFFSETX / 2
+    fn get_size(&self) -> Vector2 {
+        ALIEN_SPRITE_SIZES[self.kind]
+    }
+
+    // Tells if the alien stays within the screen after moving by `dx`
+    pub fn can_move_by(&self, dx: i32) -> bool {
+        let x = self.position.x as i32 + dx;
+        x >= OFFSETX / 2 && x + self.get_size().x as i32 <= WINDOW_WIDTH - OFFSETX / 2
     }
 
-    pub fn has_overflowed_left(&self) -> bool {
-        (self.position.x as i32) < (OFFSETX / 2)
+    // Every alien type fires its own kind of projectile
+    pub fn get_laser_kind(&self) -> LaserKind {
+        match self.kind {
+            ALIEN1 => LaserKind::Plunger,
+            ALIEN2 => LaserKind::Squiggly,
+            _ => LaserKind::Rolling,
+        }
     }
 
     pub fn get_laser_position(&self) -> Vector2 {
-        let width = self.texture[self.kind].width as f32;
-        let height = self.texture[self.kind].height as f32;
+        let size = self.get_size();
         ray::Vector2 {
-            x: self.position.x + width / 2.,
-            y: self.position.y + height,
+            x: self.position.x + size.x / 2.,
+            y: self.position.y + size.y,
         }
     }
 
     pub fn move_down(&mut self, distance: usize) {
         self.position.y += distance as f32;
+        self.next_frame();
     }
 
     pub fn set_inactive(&mut self) {
@@ -87,26 +87,38 @@ impl Alien {
         self.active
     }
 
-    pub fn update(&mut self, direction: i32) {
-        self.position.x += direction as f32;
+    pub fn update(&mut self, dx: i32) {
+        self.position.x += dx as f32;
+        self.next_frame();
+    }
+
+    fn next_frame(&mut self) {
+        self.frame = (self.frame + 1) % ALIEN_FRAMES;
+    }
+
+    pub fn get_center(&self) -> Vector2 {
+        let size = self.get_size();
+        Vector2 {
+            x: self.position.x + size.x / 2.,
+            y: self.position.y + size.y / 2.,
+        }
     }
 
-    pub fn draw(&self) {
+    pub fn draw(&self, resources: &Resources) {
         unsafe {
-            ray::DrawTextureV(
-                self.texture[self.kind],
-                self.position,
-                constants::COLOR_WHITE,
-            );
+            let texture = resources.get_texture(ALIENS_TEXTURE);
+            let frame = Alien::get_frame_rect(self.kind, self.frame);
+            ray::DrawTextureRec(texture, frame, self.position, constants::COLOR_WHITE);
         }
     }
 
     pub fn get_rect(&self) -> ray::Rectangle {
+        let size = self.get_size();
         ray::Rectangle {
             x: self.position.x,
             y: self.position.y,
-            width: self.texture[self.kind].width as f32,
-            height: self.texture[self.kind].height as f32,
+            width: size.x,
+            height: size.y,
         }
     }
 }
@@ -1,10 +1,10 @@
 use crate::constants::*;
-use crate::log;
 use raylib_ffi::IsSoundPlaying;
 use raylib_ffi::LoadSoundFromWave;
 use raylib_ffi::LoadWaveFromMemory;
 use raylib_ffi::PlaySound;
 use raylib_ffi::SetMusicVolume;
+use raylib_ffi::SetSoundPitch;
 use raylib_ffi::Sound;
 use raylib_ffi::{
     rl_str, Font, LoadFontFromMemory, LoadMusicStreamFromMemory, Music, PlayMusicStream,
@@ -19,6 +19,8 @@ pub struct Assets {
     alien_explosion: Sound,
     ship_explosion: Sound,
     mystery_sound: Sound,
+    alien_step: Sound,
+    powerup: Sound,
 }
 
 impl Drop for Assets {
@@ -42,6 +44,18 @@ macro_rules! embed_ogg_sound {
     }};
 }
 
+macro_rules! embed_wav_sound {
+    ($snd:expr) => {{
+        let sound_data = include_bytes!($snd);
+        let sound_wav = LoadWaveFromMemory(
+            rl_str!(".wav"),
+            sound_data.as_ptr(),
+            sound_data.len() as i32,
+        );
+        LoadSoundFromWave(sound_wav)
+    }};
+}
+
 macro_rules! embed_ttf_font {
     ($fname:expr, $size: expr) => {{
         let font_data = include_bytes!($fname);
@@ -50,7 +64,7 @@ macro_rules! embed_ttf_font {
             font_data.as_ptr(),
             font_data.len() as i32,
             $size,
-            0 as *mut i32,
+            std::ptr::null_mut::<i32>(),
             0,
         )
     }};
@@ -74,6 +88,8 @@ impl Assets {
                 alien_explosion: embed_ogg_sound!("../assets/sounds/alien_explosion.ogg"),
                 ship_explosion: embed_ogg_sound!("../assets/sounds/ship_explosion.ogg"),
                 mystery_sound: embed_ogg_sound!("../assets/sounds/mystery.ogg"),
+                alien_step: embed_ogg_sound!("../assets/sounds/alien_step.ogg"),
+                powerup: embed_wav_sound!("../assets/sounds/powerup.wav"),
             }
         }
     }
@@ -119,6 +135,21 @@ impl Assets {
         }
     }
 
+    pub fn play_powerup_sound(&self) {
+        unsafe {
+            PlaySound(self.powerup);
+        }
+    }
+
+    // The four descending notes of the marching heartbeat
+    pub fn play_alien_step_sound(&self, note: usize) {
+        const PITCHES: [f32; 4] = [1.0, 0.94, 0.89, 0.84];
+        unsafe {
+            SetSoundPitch(self.alien_step, PITCHES[note % PITCHES.len()]);
+            PlaySound(self.alien_step);
+        }
+    }
+
     pub fn play_mystery_sound(&self) {
         unsafe {
             if !IsSoundPlaying(self.mystery_sound) {
@@ -0,0 +1,108 @@
+// The game clock.
+//
+// The simulation always advances in fixed steps of TICK_DT seconds, no
+// matter how fast frames are rendered: each frame the clock measures the
+// elapsed real time and tells the caller how many steps are due.
+
+use raylib_ffi::GetTime;
+
+pub const TICK_RATE: u32 = 60;
+pub const TICK_DT: f64 = 1. / TICK_RATE as f64;
+
+// Longest frame time taken into account (avoids a burst of catch-up steps
+// after a stall, e.g. while the window is being dragged)
+const MAX_FRAME_TIME: f64 = 0.25;
+
+/// Where the clock reads the current (real) time from
+pub trait TimeSource {
+    fn now(&self) -> f64;
+}
+
+/// Real time as measured by raylib since the window was opened
+pub struct RaylibTime;
+
+impl TimeSource for RaylibTime {
+    fn now(&self) -> f64 {
+        unsafe { GetTime() }
+    }
+}
+
+pub struct GameClock {
+    source: Box<dyn TimeSource>,
+    last_time: f64,
+    accumulator: f64,
+}
+
+impl GameClock {
+    pub fn new(source: Box<dyn TimeSource>) -> Self {
+        let last_time = source.now();
+        Self {
+            source,
+            last_time,
+            accumulator: 0.,
+        }
+    }
+
+    /// Returns the number of fixed steps due since the previous call
+    pub fn advance(&mut self) -> u32 {
+        let now = self.source.now();
+        self.accumulator += (now - self.last_time).clamp(0., MAX_FRAME_TIME);
+        self.last_time = now;
+
+        let mut ticks = 0;
+        while self.accumulator >= TICK_DT {
+            self.accumulator -= TICK_DT;
+            ticks += 1;
+        }
+        ticks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct ManualTime(Rc<Cell<f64>>);
+
+    impl TimeSource for ManualTime {
+        fn now(&self) -> f64 {
+            self.0.get()
+        }
+    }
+
+    fn manual_clock() -> (GameClock, Rc<Cell<f64>>) {
+        let time = Rc::new(Cell::new(0.));
+        let clock = GameClock::new(Box::new(ManualTime(time.clone())));
+        (clock, time)
+    }
+
+    #[test]
+    fn slow_frames_run_several_ticks() {
+        let (mut clock, time) = manual_clock();
+        time.set(2.5 * TICK_DT);
+        assert_eq!(clock.advance(), 2);
+        // the leftover half tick is carried over to the next frame
+        time.set(3.2 * TICK_DT);
+        assert_eq!(clock.advance(), 1);
+    }
+
+    #[test]
+    fn fast_frames_accumulate_until_a_tick_is_due() {
+        let (mut clock, time) = manual_clock();
+        time.set(0.4 * TICK_DT);
+        assert_eq!(clock.advance(), 0);
+        time.set(0.8 * TICK_DT);
+        assert_eq!(clock.advance(), 0);
+        time.set(1.2 * TICK_DT);
+        assert_eq!(clock.advance(), 1);
+    }
+
+    #[test]
+    fn long_stalls_are_capped() {
+        let (mut clock, time) = manual_clock();
+        time.set(10.);
+        assert!(clock.advance() <= (MAX_FRAME_TIME / TICK_DT).ceil() as u32);
+    }
+}
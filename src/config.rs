@@ -0,0 +1,440 @@
+// Gameplay tuning, read from spaceinv.toml at startup.
+//
+// Every value is optional: whatever the file doesn't set keeps its default,
+// which is the matching constant of constants.rs (or LEVEL_CURVE below for
+// the levels). `spaceinv --dump-config` prints the complete default
+// configuration as a starting point.
+
+use crate::constants::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const CONFIG_FILE: &str = "spaceinv.toml";
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub game: GameConfig,
+    pub player: PlayerConfig,
+    pub aliens: AliensConfig,
+    pub mystery_ship: MysteryShipConfig,
+    pub powerups: PowerUpsConfig,
+    /// The difficulty curve, one entry per level
+    pub levels: Vec<LevelConfig>,
+}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    /// Seed of the gameplay random generator (random when not set)
+    pub seed: Option<u64>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerConfig {
+    pub lives: usize,
+    /// Pixels per tick
+    pub speed: f64,
+    /// Pixels per tick
+    pub laser_speed: f64,
+    /// Seconds between two shots
+    pub laser_cooldown: f64,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AliensConfig {
+    /// Points for ALIEN1, ALIEN2 and ALIEN3
+    pub scores: [usize; 3],
+    /// Pixels the formation drops when it reaches a side
+    pub down_distance: usize,
+    /// Pixels every alien moves on each step of the formation
+    pub step_size: usize,
+    /// Ticks between two steps of the formation, at least
+    pub min_step_ticks: u32,
+    /// Pixels per tick
+    pub laser_speed: f64,
+    /// Seconds between two alien shots
+    pub laser_interval: f64,
+    /// Chance (0 to 1) that a shot is aimed at the player
+    pub aimed_shot_chance: f64,
+    /// Most alien lasers on screen at once, by level (the last value holds
+    /// for all the following levels)
+    pub max_lasers: Vec<usize>,
+    /// Points for shooting down an alien laser
+    pub laser_bonus: usize,
+    /// Chance (0 to 1) that a player shot destroys a rolling, plunger and
+    /// squiggly projectile
+    pub laser_shoot_down_chances: [f64; 3],
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MysteryShipConfig {
+    /// Pixels per tick
+    pub speed: f64,
+    /// Seconds between two appearances (picked at random in the range)
+    pub min_interval: f64,
+    pub max_interval: f64,
+    /// Points for a hit, picked by the number of shots fired so far: the
+    /// n-th shot of the game scores scores[n % the number of scores]
+    pub scores: Vec<usize>,
+    /// Chances of the classic, bomber, gold and carrier ships, relative to
+    /// each other
+    pub weights: [u32; 4],
+    /// Speeds of the classic, bomber, gold and carrier ships, as multiples
+    /// of speed
+    pub speed_factors: [f64; 4],
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerUpsConfig {
+    /// Chance (0 to 1) that a destroyed alien drops a power-up
+    pub drop_chance: f64,
+    /// Seconds the rapid fire, double shot, piercing and shield power-ups
+    /// last
+    pub durations: [f64; 4],
+}
+
+/// How hard a level is, compared to the first one. The last entry of the
+/// curve holds for all the following levels.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LevelConfig {
+    /// Pixels the formation starts lower
+    pub formation_drop: usize,
+    /// Multiplies the alien shots per second
+    pub fire_rate: f64,
+    /// Multiplies the alien laser speed
+    pub laser_speed: f64,
+    /// Multiplies the time between two mystery ships
+    pub mystery_interval: f64,
+}
+
+// The difficulty curve, one entry per level (the last one holds for all the
+// following levels)
+pub const LEVEL_CURVE: [LevelConfig; 5] = [
+    LevelConfig {
+        formation_drop: 0,
+        fire_rate: 1.,
+        laser_speed: 1.,
+        mystery_interval: 1.,
+    },
+    LevelConfig {
+        formation_drop: 16,
+        fire_rate: 1.15,
+        laser_speed: 1.1,
+        mystery_interval: 0.9,
+    },
+    LevelConfig {
+        formation_drop: 32,
+        fire_rate: 1.3,
+        laser_speed: 1.2,
+        mystery_interval: 0.8,
+    },
+    LevelConfig {
+        formation_drop: 48,
+        fire_rate: 1.45,
+        laser_speed: 1.3,
+        mystery_interval: 0.7,
+    },
+    LevelConfig {
+        formation_drop: 64,
+        fire_rate: 1.6,
+        laser_speed: 1.4,
+        mystery_interval: 0.6,
+    },
+];
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            game: GameConfig::default(),
+            player: PlayerConfig::default(),
+            aliens: AliensConfig::default(),
+            mystery_ship: MysteryShipConfig::default(),
+            powerups: PowerUpsConfig::default(),
+            levels: LEVEL_CURVE.to_vec(),
+        }
+    }
+}
+
+impl Default for PowerUpsConfig {
+    fn default() -> Self {
+        PowerUpsConfig {
+            drop_chance: POWERUP_DROP_CHANCE,
+            durations: POWERUP_DURATIONS,
+        }
+    }
+}
+
+impl Default for LevelConfig {
+    fn default() -> Self {
+        LevelConfig {
+            formation_drop: 0,
+            fire_rate: 1.,
+            laser_speed: 1.,
+            mystery_interval: 1.,
+        }
+    }
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        PlayerConfig {
+            lives: PLAYER_LIVES,
+            speed: SPACESHIP_SPEED,
+            laser_speed: -LASER_SPEED,
+            laser_cooldown: LASER_TIME,
+        }
+    }
+}
+
+impl Default for AliensConfig {
+    fn default() -> Self {
+        AliensConfig {
+            scores: ALIEN_SCORES,
+            down_distance: ALIEN_DOWN_DISTANCE,
+            step_size: ALIEN_STEP_SIZE,
+            min_step_ticks: ALIEN_MIN_STEP_TICKS,
+            laser_speed: ALIEN_LASER_SPEED,
+            laser_interval: ALIEN_LASER_INTERVAL,
+            aimed_shot_chance: ALIEN_AIMED_SHOT_CHANCE,
+            max_lasers: ALIEN_MAX_LASERS.to_vec(),
+            laser_bonus: ALIEN_LASER_BONUS,
+            laser_shoot_down_chances: ALIEN_LASER_SHOOT_DOWN_CHANCES,
+        }
+    }
+}
+
+impl Default for MysteryShipConfig {
+    fn default() -> Self {
+        MysteryShipConfig {
+            speed: MYSTERYSHIP_SPEED,
+            min_interval: MYSTERYSHIP_MIN_INTERVAL,
+            max_interval: MYSTERYSHIP_MAX_INTERVAL,
+            scores: MYSTERYSHIP_SCORES.to_vec(),
+            weights: MYSTERYSHIP_WEIGHTS,
+            speed_factors: MYSTERYSHIP_SPEED_FACTORS,
+        }
+    }
+}
+
+fn check(ok: bool, msg: impl FnOnce() -> String) -> Result<(), String> {
+    match ok {
+        true => Ok(()),
+        false => Err(msg()),
+    }
+}
+
+fn check_positive<T: PartialOrd + Default + std::fmt::Display>(
+    name: &str,
+    value: T,
+) -> Result<(), String> {
+    check(value > T::default(), || {
+        format!("{} must be greater than 0 (got {})", name, value)
+    })
+}
+
+impl MysteryShipConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        check_positive("mystery_ship.speed", self.speed)?;
+        for factor in self.speed_factors {
+            check_positive("mystery_ship.speed_factors", factor)?;
+        }
+        check_positive("mystery_ship.min_interval", self.min_interval)?;
+        check(self.min_interval < self.max_interval, || {
+            format!(
+                "mystery_ship.min_interval ({}) must be lower than mystery_ship.max_interval ({})",
+                self.min_interval, self.max_interval
+            )
+        })?;
+        check(!self.scores.is_empty(), || {
+            "mystery_ship.scores needs at least one value".to_string()
+        })?;
+        check(self.weights.iter().any(|weight| *weight > 0), || {
+            "mystery_ship.weights needs at least one weight above 0".to_string()
+        })?;
+        // the kind is picked with a roll below the total
+        let total: u64 = self.weights.iter().map(|weight| *weight as u64).sum();
+        check(total <= u32::MAX as u64, || {
+            format!(
+                "mystery_ship.weights can't add up to more than {} (got {})",
+                u32::MAX,
+                total
+            )
+        })
+    }
+}
+
+impl Config {
+    /// Loads the configuration file. A missing file gives the defaults.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(err) => return Err(format!("{}: {}", path.display(), err)),
+        };
+        Config::parse(&text).map_err(|msg| format!("{}: {}", path.display(), msg))
+    }
+
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let config: Config = toml::from_str(text).map_err(|err| err.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("the configuration can always be serialized")
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        check_positive("player.lives", self.player.lives)?;
+        check(self.player.lives <= PLAYER_MAX_LIVES, || {
+            format!(
+                "player.lives can't be more than {} (got {})",
+                PLAYER_MAX_LIVES, self.player.lives
+            )
+        })?;
+        check_positive("player.speed", self.player.speed)?;
+        check_positive("player.laser_speed", self.player.laser_speed)?;
+        check(self.player.laser_cooldown >= 0., || {
+            "player.laser_cooldown can't be negative".to_string()
+        })?;
+
+        check_positive("aliens.step_size", self.aliens.step_size)?;
+        check_positive("aliens.laser_speed", self.aliens.laser_speed)?;
+        check_positive("aliens.laser_interval", self.aliens.laser_interval)?;
+        check((0. ..=1.).contains(&self.aliens.aimed_shot_chance), || {
+            format!(
+                "aliens.aimed_shot_chance must be between 0 and 1 (got {})",
+                self.aliens.aimed_shot_chance
+            )
+        })?;
+        for chance in self.aliens.laser_shoot_down_chances {
+            check((0. ..=1.).contains(&chance), || {
+                format!(
+                    "aliens.laser_shoot_down_chances must be between 0 and 1 (got {})",
+                    chance
+                )
+            })?;
+        }
+        check(!self.aliens.max_lasers.is_empty(), || {
+            "aliens.max_lasers needs at least one value".to_string()
+        })?;
+
+        self.mystery_ship.validate()?;
+
+        check((0. ..=1.).contains(&self.powerups.drop_chance), || {
+            format!(
+                "powerups.drop_chance must be between 0 and 1 (got {})",
+                self.powerups.drop_chance
+            )
+        })?;
+        for duration in self.powerups.durations {
+            check_positive("powerups.durations", duration)?;
+        }
+
+        check(!self.levels.is_empty(), || {
+            "levels needs at least one entry".to_string()
+        })?;
+        for (index, level) in self.levels.iter().enumerate() {
+            let name = |field: &str| format!("levels[{}].{}", index, field);
+            check(level.formation_drop <= ALIEN_MAX_FORMATION_DROP, || {
+                format!(
+                    "{} can't be more than {} (got {})",
+                    name("formation_drop"),
+                    ALIEN_MAX_FORMATION_DROP,
+                    level.formation_drop
+                )
+            })?;
+            check_positive(&name("fire_rate"), level.fire_rate)?;
+            check_positive(&name("laser_speed"), level.laser_speed)?;
+            check_positive(&name("mystery_interval"), level.mystery_interval)?;
+        }
+        Ok(())
+    }
+
+    /// The difficulty of `level` (starting from 1)
+    pub fn get_level(&self, level: usize) -> &LevelConfig {
+        &self.levels[(level.max(1) - 1).min(self.levels.len() - 1)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_the_constants() {
+        let config = Config::default();
+        assert_eq!(config.player.lives, PLAYER_LIVES);
+        assert_eq!(config.aliens.scores, ALIEN_SCORES);
+        assert_eq!(config.mystery_ship.scores, MYSTERYSHIP_SCORES);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn dumped_defaults_load_back() {
+        let config = Config::default();
+        assert_eq!(Config::parse(&config.to_toml()).unwrap(), config);
+    }
+
+    #[test]
+    fn dumping_a_loaded_dump_gives_the_same_text() {
+        let text = Config::default().to_toml();
+        assert_eq!(Config::parse(&text).unwrap().to_toml(), text);
+        // the values print the way they are written
+        assert!(text.contains("laser_speed = 1.1\n"), "{}", text);
+    }
+
+    #[test]
+    fn partial_files_keep_the_other_defaults() {
+        let config = Config::parse("[player]\nlives = 5\n").unwrap();
+        assert_eq!(config.player.lives, 5);
+        assert_eq!(config.player.speed, SPACESHIP_SPEED);
+        assert_eq!(config.aliens, AliensConfig::default());
+    }
+
+    #[test]
+    fn reports_invalid_values() {
+        let err = Config::parse("[player]\nlives = 0\n").unwrap_err();
+        assert_eq!(err, "player.lives must be greater than 0 (got 0)");
+        let err = Config::parse("[player]\nlives = 9\n").unwrap_err();
+        assert_eq!(err, "player.lives can't be more than 5 (got 9)");
+
+        let err = Config::parse("[mystery_ship]\nmin_interval = 30.0\n").unwrap_err();
+        assert!(err.starts_with("mystery_ship.min_interval (30)"), "{}", err);
+
+        let err = Config::parse("[mystery_ship]\nweights = [4294967295, 1, 0, 0]\n").unwrap_err();
+        assert!(
+            err.starts_with("mystery_ship.weights can't add up"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn the_last_level_of_the_curve_holds_for_the_next_ones() {
+        let config =
+            Config::parse("[[levels]]\nfire_rate = 2.0\n[[levels]]\nformation_drop = 20\n")
+                .unwrap();
+        assert_eq!(config.get_level(1).fire_rate, 2.);
+        assert_eq!(config.get_level(2).formation_drop, 20);
+        assert_eq!(config.get_level(9), config.get_level(2));
+
+        let err = Config::parse("[[levels]]\nlaser_speed = 0.0\n").unwrap_err();
+        assert_eq!(err, "levels[0].laser_speed must be greater than 0 (got 0)");
+    }
+
+    #[test]
+    fn reports_unknown_keys_and_bad_types() {
+        let err = Config::parse("[player]\nlifes = 5\n").unwrap_err();
+        assert!(err.contains("unknown field `lifes`"), "{}", err);
+
+        let err = Config::parse("[aliens]\nscores = [1, 2]\n").unwrap_err();
+        assert!(err.contains("line 2"), "{}", err);
+    }
+}
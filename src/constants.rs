@@ -25,6 +25,8 @@ pub const WORLD_HEIGHT: i32 = WINDOW_HEIGHT + OFFSETY;
 pub const WINDOW_TITLE: &str = "Rust Space Invaders";
 
 pub const PLAYER_LIVES: usize = 3;
+// As many lives as the HUD has room for, left of the power-ups
+pub const PLAYER_MAX_LIVES: usize = 5;
 
 pub const WINDOW_BKG_COLOR: Color = Color {
     r: 29,
@@ -66,6 +68,40 @@ pub const GUI_SCORE_VALUE_POS: Vector2 = Vector2 { x: 50., y: 40. };
 pub const GUI_HIGH_SCORE_TEXT_POS: Vector2 = Vector2 { x: 570., y: 15. };
 pub const GUI_HIGH_SCORE_VALUE_POS: Vector2 = Vector2 { x: 655., y: 40. };
 
+pub const LEADERBOARD_RECT: Rectangle = Rectangle {
+    x: 100.,
+    y: 90.,
+    width: 600.,
+    height: 560.,
+};
+
+// PAUSE MENU CONSTANTS
+
+// Shades the frozen playfield under the pause menu
+pub const PAUSE_SHADE_COLOR: Color = Color {
+    r: 29,
+    g: 29,
+    b: 27,
+    a: 200,
+};
+pub const PAUSE_TITLE_Y: f32 = 260.;
+
+// GAMEPAD CONSTANTS
+
+// Stick positions closer to the center than this are ignored
+pub const GAMEPAD_DEADZONE: f32 = 0.25;
+// Where the controller (dis)connected notice shows, and for how long
+pub const GAMEPAD_NOTICE_Y: f32 = 690.;
+pub const GAMEPAD_NOTICE_SECONDS: f64 = 2.5;
+
+// TITLE SCREEN CONSTANTS
+
+pub const TITLE_FONT_SIZE: f32 = 68.;
+pub const TITLE_LOGO_Y: f32 = 110.;
+pub const TITLE_MENU_Y: f32 = 300.;
+pub const TITLE_SCORE_TABLE_Y: f32 = 520.;
+pub const TITLE_SCORE_TABLE_X: f32 = 280.;
+
 pub const FRAME_COLOR: Color = Color {
     r: 243,
     g: 216,
@@ -87,10 +123,13 @@ pub const RED_COLOR: Color = Color {
     a: 255,
 };
 
+// Speeds are in pixels per simulation tick (see clock::TICK_RATE),
+// times and intervals in seconds of simulation time
+
 // LASER CONSTANTS
 
 pub const LASER_TIME: f64 = 0.35;
-pub const LASER_SPEED: f32 = -6.;
+pub const LASER_SPEED: f64 = -6.;
 pub const LASER_WIDTH: f32 = 4.;
 pub const LASER_HEIGHT: f32 = 15.;
 pub const LASER_SIZE: Vector2 = Vector2 {
@@ -106,17 +145,69 @@ pub const LASER_COLOR: Color = Color {
 
 // SPACESHIP CONSTANTS
 
-pub const SPACESHIP_SPEED: f32 = 7.;
+pub const SPACESHIP_SPEED: f64 = 7.;
 pub const SPACESHIP_YOFFSET: i32 = OFFSETY;
 pub const SPACESHIP_XOFFSET: i32 = OFFSETX / 2;
+// Size of the spaceship sprite (must match the embedded image)
+pub const SPACESHIP_SIZE: Vector2 = Vector2 { x: 44., y: 28. };
 
 // MYSTERYSHIP CONSTANTS
 
-pub const MYSTERYSHIP_SPEED: f32 = 3.;
+pub const MYSTERYSHIP_SPEED: f64 = 3.;
 pub const MYSTERYSHIP_YPOS: f32 = 90.;
 pub const MYSTERYSHIP_MIN_INTERVAL: f64 = 10.;
 pub const MYSTERYSHIP_MAX_INTERVAL: f64 = 20.;
-pub const MYSTERYSHIP_SCORE: usize = 500;
+// As in the arcade, the points depend on the number of shots fired so
+// far: the n-th shot of the game scores MYSTERYSHIP_SCORES[n % 15]
+pub const MYSTERYSHIP_SCORES: [usize; 15] = [
+    100, 50, 50, 100, 150, 100, 100, 50, 300, 100, 100, 100, 50, 150, 100,
+];
+// How long the points scored stay on screen
+pub const SCORE_POPUP_TICKS: u32 = 60;
+pub const MYSTERYSHIP_POPUP_COLOR: Color = Color {
+    r: 230,
+    g: 41,
+    b: 55,
+    a: 255,
+};
+// Size of the mystery ship sprite (must match the embedded image)
+pub const MYSTERYSHIP_SIZE: Vector2 = Vector2 { x: 58., y: 25. };
+// Chances of the classic, bomber, gold and carrier ships, relative to each
+// other
+pub const MYSTERYSHIP_WEIGHTS: [u32; 4] = [10, 3, 1, 3];
+// Speeds of the classic, bomber, gold and carrier ships, as multiples of
+// MYSTERYSHIP_SPEED: the bomber lingers over the player, the gold one rushes
+pub const MYSTERYSHIP_SPEED_FACTORS: [f64; 4] = [1., 0.8, 1.8, 1.3];
+// Seconds between two bombs of the bomber
+pub const MYSTERYSHIP_BOMB_INTERVAL: f64 = 0.5;
+
+// POWER-UP CONSTANTS
+
+pub const POWERUP_SIZE: f32 = 24.;
+// Chance that a destroyed alien drops a power-up
+pub const POWERUP_DROP_CHANCE: f64 = 0.04;
+// Seconds the rapid fire, double shot, piercing and shield power-ups last
+pub const POWERUP_DURATIONS: [f64; 4] = [10., 12., 8., 6.];
+// Rapid fire divides the time between two shots by this
+pub const RAPID_FIRE_FACTOR: f64 = 3.;
+// Pixels between the two lasers of the double shot
+pub const DOUBLE_SHOT_SPREAD: f32 = 16.;
+pub const SHIELD_COLOR: Color = Color {
+    r: 40,
+    g: 200,
+    b: 220,
+    a: 160,
+};
+// Where the HUD lists the timed power-ups, one after the other
+pub const GUI_POWERUP_POS: Vector2 = Vector2 { x: 300., y: 740. };
+pub const GUI_POWERUP_INC: f32 = 70.;
+pub const POWERUP_SPEED: f32 = 2.;
+pub const POWERUP_COLOR: Color = Color {
+    r: 40,
+    g: 200,
+    b: 220,
+    a: 255,
+};
 
 // BLOCK CONSTANTS
 
@@ -166,14 +257,53 @@ pub const OBSTACLE_GRID: [&[u8; GRID_WIDTH]; GRID_HEIGHT ] = [
 
 pub const ALIEN_SCORES: [usize; 3] = [100, 200, 300];
 
+// Sprite sizes of ALIEN1, ALIEN2 and ALIEN3 (must match the embedded images)
+pub const ALIEN_SPRITE_SIZES: [Vector2; 3] = [
+    Vector2 { x: 38., y: 34. },
+    Vector2 { x: 44., y: 34. },
+    Vector2 { x: 41., y: 40. },
+];
+
+// The aliens sprite sheet has a row per alien type and a column per
+// animation frame, every frame at the top left of its cell
+pub const ALIEN_SHEET_CELL: Vector2 = Vector2 { x: 44., y: 40. };
+pub const ALIEN_FRAMES: usize = 2;
+
+// Size of the explosion sprite, and how long it stays on screen
+pub const EXPLOSION_SIZE: Vector2 = Vector2 { x: 39., y: 24. };
+pub const EXPLOSION_TICKS: u32 = 16;
+// Reach of the spark left by two lasers hitting each other, and its life
+pub const SPARK_SIZE: f32 = 8.;
+pub const SPARK_TICKS: u32 = 8;
+
 pub const ALIEN_ROWS: usize = 5;
 pub const ALIEN_COLUMNS: usize = 11;
 pub const ALIEN_SIZE: usize = 55;
 pub const ALIEN_OFFSET_X: usize = 75;
 pub const ALIEN_OFFSET_Y: usize = 110;
-pub const ALIEN_DOWN_DISTANCE: usize = 4;
-pub const ALIEN_LASER_SPEED: f32 = 6.;
+pub const ALIEN_DOWN_DISTANCE: usize = 16;
+// The bottom of the spaceship row: an alien reaching it ends the game
+pub const ALIEN_GROUND_Y: f32 = (WORLD_HEIGHT - SPACESHIP_YOFFSET) as f32;
+// The formation moves one alien per tick, by this many pixels
+pub const ALIEN_STEP_SIZE: usize = 10;
+// Minimum number of ticks between two steps of the whole formation (caps
+// the speed when only a few aliens are left)
+pub const ALIEN_MIN_STEP_TICKS: u32 = 4;
+pub const ALIEN_LASER_SPEED: f64 = 6.;
 pub const ALIEN_LASER_INTERVAL: f64 = 0.35;
+// Keeps the lowest start of the formation well above the obstacles
+pub const ALIEN_MAX_FORMATION_DROP: usize = 160;
+
+// Chance that a shot comes from the column above the spaceship
+pub const ALIEN_AIMED_SHOT_CHANCE: f64 = 0.3;
+// Most alien lasers on screen at once, by level (the last value holds for
+// all the following levels)
+pub const ALIEN_MAX_LASERS: [usize; 5] = [2, 3, 3, 4, 5];
+// Points for shooting down an alien laser
+pub const ALIEN_LASER_BONUS: usize = 10;
+// Chance that a player shot destroys a rolling, plunger and squiggly
+// projectile (when it fails, only the player shot is lost)
+pub const ALIEN_LASER_SHOOT_DOWN_CHANCES: [f64; 3] = [0.5, 1., 1.];
 
 // SOUND CONSTANTS
 
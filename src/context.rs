@@ -1,16 +1,107 @@
-use raylib_ffi as ray;
-use std::cell::RefCell;
+// The state shared by all the scenes: assets and textures, the simulation,
+// the leaderboard, the key bindings and the session seed.
+
+use crate::assets::Assets;
+use crate::config::Config;
+use crate::input::{ActionMap, CONTROLS_FILE};
+use crate::leaderboard::{self, Entry, Leaderboard, LEADERBOARD_FILE};
+use crate::log;
+use crate::resources::Resources;
+use crate::storage;
+use crate::wave::Wave;
+use crate::world::World;
 
 pub struct Context {
-    pub rl: RefCell<RaylibHandle>,
-    pub thread: RefCell<RaylibThread>,
+    pub assets: Box<Assets>,
+    pub resources: Resources,
+    pub world: World,
+    pub leaderboard: Leaderboard,
+    pub actions: ActionMap,
+    // Set when the current game has ended
+    pub game_over: bool,
+    // Set from the start of a game until the player leaves it for the
+    // menu: the score of an abandoned game isn't a high score
+    playing: bool,
+    // false when playing a replay back: the files on disk are left alone
+    persist: bool,
+    seed: u64,
+    games_started: u64,
 }
 
 impl Context {
-    pub fn new(rl: RaylibHandle, thread: RaylibThread) -> Self {
-        Self {
-            rl: RefCell::new(rl),
-            thread: RefCell::new(thread),
+    /// Creates the context of a session started from `seed`, playing
+    /// `waves` for the first levels, with the leaderboard of the session
+    /// start (the window must already be open to load the assets). The
+    /// leaderboard and the controls are only saved when `persist` is set.
+    pub fn new(
+        seed: u64,
+        config: &Config,
+        waves: Vec<Wave>,
+        leaderboard: Leaderboard,
+        persist: bool,
+    ) -> Self {
+        Context {
+            assets: Box::new(Assets::new()),
+            resources: Resources::new(),
+            world: World::new(seed, config, waves),
+            leaderboard,
+            actions: ActionMap::load_or_recover(&storage::data_file(CONTROLS_FILE)),
+            game_over: false,
+            playing: false,
+            persist,
+            seed,
+            games_started: 0,
+        }
+    }
+
+    pub fn init_game(&mut self) {
+        // every game of the session gets its own seed, derived from the
+        // session one so that a replay can start the same games again
+        let seed = self.seed.wrapping_add(self.games_started);
+        self.games_started += 1;
+        log::info(format!("Game seed: {}", seed).as_str());
+        self.world.init_game(seed);
+        self.game_over = false;
+        self.playing = true;
+        self.assets.play_music();
+    }
+
+    /// Leaves the current game for the menu
+    pub fn quit_game(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn high_score(&self) -> usize {
+        match self.playing {
+            true => self.leaderboard.best_score().max(self.world.score),
+            false => self.leaderboard.best_score(),
+        }
+    }
+
+    /// Adds the run that just ended to the leaderboard and returns its rank
+    pub fn save_run(&mut self, initials: &[u8]) -> Option<usize> {
+        let entry = Entry {
+            initials: String::from_utf8_lossy(initials).into_owned(),
+            score: self.world.score,
+            level: self.world.level,
+            date: leaderboard::today(),
+        };
+        let rank = self.leaderboard.insert(entry);
+        if !self.persist {
+            return rank;
+        }
+        if let Err(err) = self.leaderboard.save(&storage::data_file(LEADERBOARD_FILE)) {
+            log::warning(format!("Could not save the leaderboard: {}", err).as_str());
+        }
+        rank
+    }
+
+    pub fn save_controls(&self) {
+        if !self.persist {
+            return;
+        }
+        if let Err(err) = self.actions.save(&storage::data_file(CONTROLS_FILE)) {
+            log::warning(format!("Could not save the controls: {}", err).as_str());
         }
     }
 }
@@ -0,0 +1,68 @@
+// What's left of a destroyed alien, or of two lasers that hit each other,
+// for a few ticks
+
+use crate::constants::*;
+use crate::resources::{Resources, EXPLOSION_TEXTURE};
+use raylib_ffi::{DrawLineEx, DrawTextureV, Vector2};
+
+#[derive(Clone)]
+pub struct Explosion {
+    position: Vector2,
+    ticks_left: u32,
+    // sparks have no sprite, they are drawn as a few short lines
+    spark: bool,
+}
+
+impl Explosion {
+    /// Creates an explosion centered on `center`
+    pub fn new(center: Vector2) -> Self {
+        Explosion {
+            position: Vector2 {
+                x: center.x - EXPLOSION_SIZE.x / 2.,
+                y: center.y - EXPLOSION_SIZE.y / 2.,
+            },
+            ticks_left: EXPLOSION_TICKS,
+            spark: false,
+        }
+    }
+
+    /// Creates a spark centered on `center`
+    pub fn spark(center: Vector2) -> Self {
+        Explosion {
+            position: center,
+            ticks_left: SPARK_TICKS,
+            spark: true,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.ticks_left > 0
+    }
+
+    pub fn update(&mut self) {
+        self.ticks_left = self.ticks_left.saturating_sub(1);
+    }
+
+    pub fn draw(&self, resources: &Resources) {
+        if self.spark {
+            self.draw_spark();
+            return;
+        }
+        unsafe {
+            let texture = resources.get_texture(EXPLOSION_TEXTURE);
+            DrawTextureV(texture, self.position, COLOR_WHITE);
+        }
+    }
+
+    // Four rays growing as the spark fades
+    fn draw_spark(&self) {
+        let length = SPARK_SIZE * (SPARK_TICKS - self.ticks_left + 1) as f32 / SPARK_TICKS as f32;
+        for (dx, dy) in [(1., 1.), (1., -1.), (-1., 1.), (-1., -1.)] {
+            let end = Vector2 {
+                x: self.position.x + dx * length,
+                y: self.position.y + dy * length,
+            };
+            unsafe { DrawLineEx(self.position, end, 2., LASER_COLOR) };
+        }
+    }
+}
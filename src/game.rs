@@ -1,58 +1,67 @@
 use std::os::raw::c_int;
-use std::{fs::File, io::Write};
 
-use crate::alien::Alien;
-use crate::assets::Assets;
-//use crate::audio::Audio;
+use crate::clock::{GameClock, RaylibTime, TICK_RATE};
+use crate::config::Config;
 use crate::constants::*;
-//use crate::context::Context;
-use crate::laser::Laser;
+use crate::context::Context;
+use crate::gamepad::{Gamepad, GamepadEvent, RaylibPads};
+use crate::input::RaylibKeys;
+use crate::leaderboard::Leaderboard;
 use crate::log;
-use crate::mysteryship::MysteryShip;
-use crate::obstacle::Obstacle;
-use crate::spaceship::Spaceship;
-use rand::Rng;
-
+use crate::replay::{Replay, ReplayRecorder};
+use crate::scene::{SceneStack, Transition};
+use crate::title::TitleScene;
+use crate::ui;
+use crate::wave::Wave;
+use crate::world::TickInput;
+
+use raylib_ffi::CloseWindow;
 use raylib_ffi::{
     enums::{KeyboardKey, TraceLogLevel},
-    rl_str, BeginDrawing, CheckCollisionRecs, ClearBackground, CloseAudioDevice, Color, DrawLineEx,
-    DrawRectangleGradientH, DrawRectangleLines, DrawRectangleRoundedLinesEx, DrawText, DrawTextEx,
-    EndDrawing, GetTime, InitAudioDevice, InitWindow, IsKeyDown, IsKeyPressed, MeasureText,
-    SetTargetFPS, SetTraceLogLevel, Vector2, WindowShouldClose,
+    rl_str, BeginDrawing, ClearBackground, CloseAudioDevice, DrawRectangleRoundedLinesEx,
+    EndDrawing, InitAudioDevice, InitWindow, IsWindowFocused, SetExitKey, SetTargetFPS,
+    SetTraceLogLevel, WindowShouldClose,
 };
-use raylib_ffi::{CloseWindow, SetMusicVolume};
 
-#[derive(Clone, PartialEq)]
-enum GameState {
-    Running,
-    GameOver,
-    LevelUp,
-    Paused,
-    Quit,
+/// The player input for a single tick: the actions that drive the
+/// simulation plus the ones handled by the game itself
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct PlayerInput {
+    pub tick: TickInput,
+    pub pause: bool,
+    pub confirm: bool,
+    pub back: bool,
+    pub debug_game_over: bool,
+    pub debug_level_up: bool,
+    pub nav_up: bool,
+    pub nav_down: bool,
+    pub nav_left: bool,
+    pub nav_right: bool,
+    pub focus_lost: bool,
+    // the raw key pressed, for the controls screen
+    pub key: Option<c_int>,
 }
 
 pub struct Game {
-    assets: Box<Assets>,
-    spaceship: Box<Spaceship>,
-    lasers: Vec<Laser>,
-    obstacles: Vec<Obstacle>,
-    aliens: Vec<Box<Alien>>,
-    aliens_direction: i32,
-    alien_lasers: Vec<Laser>,
-    time_alien_last_fired: f64,
-    mysteryship: Box<MysteryShip>,
-    mysteryship_spawn_interval: f64,
-    time_last_spawned: f64,
-    lives: usize,
-    level: usize,
-    score: usize,
-    high_score: usize,
-    state: GameState,
+    context: Context,
+    scenes: SceneStack<Context>,
+    clock: GameClock,
+    pending_input: PlayerInput,
+    gamepad: Gamepad,
+    // shown over every scene when a gamepad comes or goes
+    notice: String,
+    notice_ticks: u32,
+    replay: Option<Replay>,
+    recorder: Option<ReplayRecorder>,
+    window_focused: bool,
 }
 
 impl Drop for Game {
     fn drop(&mut self) {
         log::info("Game is dropping !!!");
+        let textures = self.context.resources.get_loaded_count();
+        log::info(format!("Unloading {} textures", textures).as_str());
+        self.context.resources.unload_all();
         unsafe {
             CloseWindow();
             CloseAudioDevice();
@@ -61,397 +70,117 @@ impl Drop for Game {
 }
 
 impl Game {
-    pub fn new() -> Self {
+    /// Creates the game for a session started from `seed` and
+    /// `leaderboard`, optionally playing back a replay and/or recording the
+    /// session to a file
+    pub fn new(
+        seed: u64,
+        config: &Config,
+        waves: Vec<Wave>,
+        leaderboard: Leaderboard,
+        replay: Option<Replay>,
+        recorder: Option<ReplayRecorder>,
+    ) -> Self {
         unsafe {
             InitAudioDevice();
             InitWindow(WORLD_WIDTH, WORLD_HEIGHT, rl_str!(WINDOW_TITLE));
             SetTargetFPS(60);
             SetTraceLogLevel(TraceLogLevel::Error as i32);
+            // ESC goes back in the menus rather than closing the window
+            SetExitKey(KeyboardKey::Null as c_int);
         }
 
-        let mut game = Game {
-            assets: Box::new(Assets::new()),
-            spaceship: Box::new(Spaceship::new()),
-            lasers: Vec::new(),
-            obstacles: Vec::new(),
-            aliens: Vec::new(),
-            aliens_direction: 1,
-            alien_lasers: Vec::new(),
-            time_alien_last_fired: 0.,
-            mysteryship: Box::new(MysteryShip::new()),
-            mysteryship_spawn_interval: rand::thread_rng()
-                .gen_range(MYSTERYSHIP_MIN_INTERVAL..MYSTERYSHIP_MAX_INTERVAL),
-            time_last_spawned: 0.,
-            lives: PLAYER_LIVES,
-            level: 1,
-            score: 0,
-            high_score: 0,
-            state: GameState::Running,
-        };
-
-        game.create_obstacles();
-        game.create_aliens();
-        game.load_high_score();
-
-        game
+        Game {
+            // a replay being played back doesn't change the saved files
+            context: Context::new(seed, config, waves, leaderboard, replay.is_none()),
+            scenes: SceneStack::new(Box::new(TitleScene::default())),
+            clock: GameClock::new(Box::new(RaylibTime)),
+            pending_input: PlayerInput::default(),
+            gamepad: Gamepad::default(),
+            notice: String::new(),
+            notice_ticks: 0,
+            replay,
+            recorder,
+            window_focused: true,
+        }
     }
 
     pub fn run(&mut self) {
-        while self.state != GameState::Quit {
-            self.handle_input();
+        while !self.scenes.is_empty() {
+            self.poll_input();
             self.update();
             self.draw();
         }
     }
 
-    pub fn init_level(&mut self) {
-        self.level += 1;
-        self.aliens_direction = 1;
-        self.mysteryship_spawn_interval =
-            rand::thread_rng().gen_range(MYSTERYSHIP_MIN_INTERVAL..MYSTERYSHIP_MAX_INTERVAL);
-        self.time_last_spawned = 0.0;
-        self.time_alien_last_fired = 0.0;
-        self.state = GameState::Running;
-    }
-
-    pub fn init_game(&mut self) {
-        self.lives = PLAYER_LIVES;
-        self.level = 0;
-        self.score = 0;
-        self.high_score = 0;
-        self.load_high_score();
-        self.reset_game();
-        self.init_level();
-        self.assets.play_music();
-    }
-
-    pub fn reset_game(&mut self) {
-        self.spaceship.reset();
-        self.aliens.clear();
-        self.alien_lasers.clear();
-        self.obstacles.clear();
-        self.create_obstacles();
-        self.create_aliens();
-    }
-
-    pub fn create_obstacles(&mut self) {
-        // create the obstacles
-        const GAP: usize =
-            (WORLD_WIDTH as usize - (NUM_OBSTACLES * OBSTACLE_WIDTH)) / (NUM_OBSTACLES + 1);
-        for i in 0..NUM_OBSTACLES {
-            let offset_x = (i + 1) * GAP + i * OBSTACLE_WIDTH;
-            const OFFSET_Y: usize = WORLD_HEIGHT as usize - OBSTACLE_PADDING - OFFSETY as usize;
-            self.obstacles.push(Obstacle::new(offset_x, OFFSET_Y));
-        }
-    }
-
-    pub fn create_aliens(&mut self) {
-        for row in 0..ALIEN_ROWS {
-            let alien_type = match row {
-                0 => ALIEN3,
-                1 | 2 => ALIEN2,
-                _ => ALIEN1,
-            };
-
-            for col in 0..ALIEN_COLUMNS {
-                let x = ALIEN_OFFSET_X + col * ALIEN_SIZE;
-                let y = ALIEN_OFFSET_Y + row * ALIEN_SIZE;
-                self.aliens.push(Alien::new(
-                    alien_type,
-                    Vector2 {
-                        x: x as f32,
-                        y: y as f32,
-                    },
-                ));
-            }
-        }
-    }
-
-    pub fn check_for_highscore(&mut self) {
-        if self.score > self.high_score {
-            self.high_score = self.score;
-            self.save_high_score();
-        }
-    }
-
-    pub fn save_high_score(&self) {
-        let mut out_file =
-            File::create("highscore.txt").expect("could not create or open the highscore file");
-        write!(out_file, "{}", self.high_score).expect("could not write high score to file");
-    }
-
-    pub fn load_high_score(&mut self) {
-        let hs = std::fs::read_to_string("highscore.txt");
-        match hs {
-            Ok(value) => {
-                self.high_score = value.parse::<usize>().unwrap();
-            }
-            Err(_) => {
-                self.high_score = 0;
-            }
-        }
-    }
-
-    pub fn handle_input(&mut self) {
+    // Reads the keyboard and the gamepad once per frame. Keys that are
+    // pressed (rather than held) are kept until a tick consumes them, so
+    // that none gets lost on frames that run no tick at all.
+    fn poll_input(&mut self) {
         unsafe {
             if WindowShouldClose() {
-                self.state = GameState::Quit;
-            }
-
-            if self.state == GameState::GameOver {
-                self.handle_game_over_input();
-                return;
-            }
-
-            if self.state == GameState::LevelUp {
-                self.handle_level_up_input();
-                return;
-            }
-
-            // For debug purposes!!!
-            if IsKeyPressed(KeyboardKey::G as c_int) {
-                self.state = GameState::GameOver;
-                log::info("GameOver invoked by keyboard!");
-                return;
-            }
-            if IsKeyPressed(KeyboardKey::L as c_int) {
-                self.state = GameState::LevelUp;
-                log::info("LevelUp invoked by keyboard!");
-                return;
-            }
-
-            // Handle movement and laser fire
-            if self.state == GameState::Running {
-                if IsKeyDown(KeyboardKey::Left as c_int) {
-                    self.spaceship.move_left();
-                } else if IsKeyDown(KeyboardKey::Right as c_int) {
-                    self.spaceship.move_right();
-                } else if IsKeyDown(KeyboardKey::Space as c_int) {
-                    let laser = self.spaceship.fire_laser();
-                    if laser.is_some() {
-                        self.assets.play_laser_sound();
-                        self.lasers.push(laser.unwrap());
-                    }
-                }
-            }
-
-            // Handle pause/resume
-            if IsKeyPressed(KeyboardKey::P as c_int) {
-                if self.state == GameState::Paused {
-                    self.state = GameState::Running;
-                } else if self.state == GameState::Running {
-                    self.state = GameState::Paused;
-                }
-            }
-        }
-    }
-
-    pub fn handle_game_over_input(&mut self) {
-        unsafe {
-            if IsKeyPressed(KeyboardKey::Escape as c_int) {
-                self.state = GameState::Quit;
-            }
-            if IsKeyPressed(KeyboardKey::Enter as c_int) {
-                self.reset_game();
-                self.init_game();
-            }
-        }
-    }
-
-    pub fn handle_level_up_input(&mut self) {
-        unsafe {
-            if IsKeyPressed(KeyboardKey::Enter as c_int) {
-                self.reset_game();
-                self.init_level();
+                self.scenes.apply(Transition::Quit);
             }
-        }
-    }
 
-    pub fn move_aliens(&mut self) {
-        let mut should_move_down = false;
-        for alien in self.aliens.iter_mut() {
-            if alien.has_overflowed_right() {
-                self.aliens_direction = -1;
-                should_move_down = true;
-            }
-            if alien.has_overflowed_left() {
-                self.aliens_direction = 1;
-                should_move_down = true;
+            let pending = &mut self.pending_input;
+            // moving and firing are independent, so they can be held together
+            self.context.actions.poll(&RaylibKeys, pending);
+            if let Some(event) = self.gamepad.poll(&RaylibPads, pending) {
+                let (notice, detail) = match event {
+                    GamepadEvent::Connected(name) => ("CONTROLLER CONNECTED", name),
+                    GamepadEvent::Disconnected => ("CONTROLLER DISCONNECTED", String::new()),
+                };
+                log::info(format!("{} {}", notice, detail).trim_end());
+                self.notice = notice.to_string();
+                self.notice_ticks = (GAMEPAD_NOTICE_SECONDS * TICK_RATE as f64) as u32;
             }
-            alien.update(self.aliens_direction);
-        }
-        if should_move_down {
-            self.move_down_aliens(ALIEN_DOWN_DISTANCE);
-        }
-    }
-
-    pub fn move_down_aliens(&mut self, distance: usize) {
-        for alien in self.aliens.iter_mut() {
-            alien.move_down(distance);
-        }
-    }
 
-    pub fn aliens_shoot_laser(&mut self) {
-        let current_time = unsafe { GetTime() };
-        if current_time - self.time_alien_last_fired >= ALIEN_LASER_INTERVAL
-            && !self.aliens.is_empty()
-        {
-            let random_index: usize = rand::thread_rng().gen_range(0..self.aliens.len());
-            let alien = &self.aliens[random_index];
-            let laser_pos = alien.get_laser_position();
-            self.alien_lasers
-                .push(Laser::new(laser_pos, ALIEN_LASER_SPEED));
-            self.time_alien_last_fired = unsafe { GetTime() };
+            let focused = IsWindowFocused();
+            pending.focus_lost |= self.window_focused && !focused;
+            self.window_focused = focused;
         }
     }
 
-    pub fn check_for_collisions(&mut self) -> bool {
-        // ================
-        // spaceship lasers
-        // ================
-        for laser in self.lasers.iter_mut() {
-            // check against aliens
-            for alien in self.aliens.iter_mut() {
-                if alien.is_active()
-                    && unsafe { CheckCollisionRecs(alien.get_rect(), laser.get_rect()) }
-                {
-                    self.score += alien.get_score();
-                    alien.set_inactive();
-                    laser.set_inactive();
-                    self.assets.play_alien_explosion_sound();
-                }
-            }
-            // check if obstacle is hit and damage it!
-            for obstacle in self.obstacles.iter_mut() {
-                for block in obstacle.blocks.iter_mut() {
-                    if unsafe { CheckCollisionRecs(block.get_rect(), laser.get_rect()) } {
-                        block.set_inactive();
-                        laser.set_inactive();
-                    }
-                }
-            }
-            // check against mystery ship
-            if unsafe { CheckCollisionRecs(self.mysteryship.get_rect(), laser.get_rect()) } {
-                self.score += MYSTERYSHIP_SCORE;
-                self.mysteryship.set_inactive();
-                laser.set_inactive();
-                self.assets.play_mystery_explosion_sound();
-            }
-            // check against alien lasers (yep, we can destroy alien lasers!)
-            // T.B.D.
-        }
-
-        // ================
-        // alien lasers
-        // ================
-        for laser in self.alien_lasers.iter_mut() {
-            // check if spaceship is hit
-            if unsafe { CheckCollisionRecs(laser.get_rect(), self.spaceship.get_rect()) } {
-                self.assets.play_ship_explosion_sound();
-                laser.set_inactive();
-                self.lives -= 1;
-                if self.lives == 0 {
-                    self.state = GameState::GameOver;
-                }
-            }
-            // check if obstacle is hit and damage it!
-            for obstacle in self.obstacles.iter_mut() {
-                for block in obstacle.blocks.iter_mut() {
-                    if unsafe { CheckCollisionRecs(block.get_rect(), laser.get_rect()) } {
-                        block.set_inactive();
-                        laser.set_inactive();
-                    }
-                }
+    // Returns the input of the next tick, from the replay being played back
+    // or else from the keyboard and the gamepad, and records it if needed
+    fn next_input(&mut self) -> PlayerInput {
+        let mut input = None;
+        if let Some(replay) = self.replay.as_mut() {
+            input = replay.next_input();
+            if input.is_none() {
+                log::info("Replay finished, back to the keyboard");
+                self.replay = None;
             }
         }
-        // Patch to fix issue in line 318 (spaceship being hit and no lives left)
-        if self.state == GameState::GameOver {
-            self.game_over();
-        }
+        let input = input.unwrap_or_else(|| {
+            let input = self.pending_input;
+            self.pending_input = PlayerInput {
+                tick: input.tick,
+                ..Default::default()
+            };
+            input
+        });
 
-        // ===========
-        // alien ships
-        // ===========
-        for alien in self.aliens.iter() {
-            // alien collision with obstacle
-            for obstacle in self.obstacles.iter_mut() {
-                for block in obstacle.blocks.iter_mut() {
-                    if unsafe { CheckCollisionRecs(block.get_rect(), alien.get_rect()) } {
-                        block.set_inactive();
-                    }
-                }
-            }
-            // alien collision with ship
-            if unsafe { CheckCollisionRecs(self.spaceship.get_rect(), alien.get_rect()) } {
-                return true;
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(err) = recorder.record(&input) {
+                log::info(format!("Recording stopped: {}", err).as_str());
+                self.recorder = None;
             }
         }
-        false
+        input
     }
 
     pub fn update(&mut self) {
-        // do nothing if game is over
-        if self.state != GameState::Running {
-            return;
-        }
-
-        // Update the music
-        self.assets.update_music();
-
-        // Update the spaceship (currently does nothing)
-        self.spaceship.update();
-
-        // Update all spaceship lasers
-        for laser in self.lasers.iter_mut() {
-            laser.update();
-        }
-
-        // Remove all inactive spaceship lasers
-        self.lasers.retain(|elem| elem.is_active());
-
-        // Remove all inactive blocks
-        for obstacle in self.obstacles.iter_mut() {
-            obstacle.remove_inactive_blocks();
-        }
-
-        // Remove all inactive aliens
-        self.aliens.retain(|elem| elem.is_active());
-        if self.aliens.is_empty() {
-            self.state = GameState::LevelUp;
-        }
-
-        // Update the aliens
-        self.move_aliens();
-
-        // Create alien lasers
-        self.aliens_shoot_laser();
-
-        // Update alien lasers
-        for laser in self.alien_lasers.iter_mut() {
-            laser.update();
-        }
-
-        // Remove all inactive alien lasers
-        self.alien_lasers.retain(|elem| elem.is_active());
-
-        // Update the mystery ship
-        let current_time = unsafe { GetTime() };
-        if current_time - self.time_last_spawned > self.mysteryship_spawn_interval {
-            self.mysteryship.spawn();
-            self.time_last_spawned = unsafe { GetTime() };
-            self.mysteryship_spawn_interval =
-                rand::thread_rng().gen_range(MYSTERYSHIP_MIN_INTERVAL..MYSTERYSHIP_MAX_INTERVAL)
-        }
-
-        if self.mysteryship.is_active() {
-            self.assets.play_mystery_sound();
-            self.mysteryship.update();
-        }
-
-        let done = self.check_for_collisions();
-        self.check_for_highscore();
-        if done {
-            self.game_over();
+        // Every tick, pause and dialogs included, reads its own input:
+        // the scene on top decides what it does with it
+        for _ in 0..self.clock.advance() {
+            self.notice_ticks = self.notice_ticks.saturating_sub(1);
+            let input = self.next_input();
+            self.scenes.handle_input(&mut self.context, &input);
+            self.scenes.update(&mut self.context);
+            if self.scenes.is_empty() {
+                return;
+            }
         }
     }
 
@@ -466,155 +195,18 @@ impl Game {
                 FRAME_THICKNESS,
                 FRAME_COLOR,
             );
-            DrawLineEx(
-                Vector2 {
-                    x: GUI_LINE_X1,
-                    y: GUI_LINE_Y,
-                },
-                Vector2 {
-                    x: GUI_LINE_X2,
-                    y: GUI_LINE_Y,
-                },
-                GUI_LINE_THICKNESS,
-                FRAME_COLOR,
-            );
-            if self.state != GameState::GameOver {
-                DrawTextEx(
-                    self.assets.get_font(),
-                    rl_str!(format!("LEVEL {:0>2}", self.level).as_str()),
-                    LEVEL_POS,
-                    FONT_SIZE as f32,
-                    FONT_SPACING,
-                    FRAME_COLOR,
-                );
-            } else {
-                DrawTextEx(
-                    self.assets.get_font(),
-                    rl_str!("GAME OVER"),
-                    LEVEL_POS,
-                    FONT_SIZE as f32,
-                    FONT_SPACING,
-                    FRAME_COLOR,
-                );
-            }
-            DrawTextEx(
-                self.assets.get_font(),
-                rl_str!("SCORE"),
-                GUI_SCORE_TEXT_POS,
-                FONT_SIZE as f32,
-                FONT_SPACING,
-                FRAME_COLOR,
-            );
-            DrawTextEx(
-                self.assets.get_font(),
-                rl_str!(format!("{:0>5}", self.score).as_str()),
-                GUI_SCORE_VALUE_POS,
-                FONT_SIZE as f32,
-                FONT_SPACING,
-                FRAME_COLOR,
-            );
-            DrawTextEx(
-                self.assets.get_font(),
-                rl_str!("HIGH SCORE"),
-                GUI_HIGH_SCORE_TEXT_POS,
-                FONT_SIZE as f32,
-                FONT_SPACING,
-                FRAME_COLOR,
-            );
-            DrawTextEx(
-                self.assets.get_font(),
-                rl_str!(format!("{:0>5}", self.high_score).as_str()),
-                GUI_HIGH_SCORE_VALUE_POS,
-                FONT_SIZE as f32,
-                FONT_SPACING,
-                FRAME_COLOR,
-            );
-
-            // DRAW OTHER OBJECTS
-
-            let mut x = GUI_LIVEIMG_X;
-            for _ in 0..self.lives {
-                self.spaceship.draw_at(x, GUI_LIVEIMG_Y);
-                x += GUI_LIVEIMG_INC;
-            }
-
-            for obstacle in self.obstacles.iter() {
-                obstacle.draw();
-            }
-            self.spaceship.draw();
-            for laser in self.lasers.iter_mut() {
-                laser.draw();
-            }
-
-            for alien in self.aliens.iter() {
-                alien.draw();
-            }
-
-            for laser in self.alien_lasers.iter_mut() {
-                laser.draw();
-            }
-
-            self.mysteryship.draw();
-
-            if self.state == GameState::GameOver {
-                self.game_over_draw();
-            }
-
-            if self.state == GameState::LevelUp {
-                self.level_up_draw();
-            }
-
-            EndDrawing();
         }
-    }
-
-    fn center_text_at(&mut self, posx: i32, posy: i32, width: i32, text: &str) {
-        const YELLOW: Color = Color {
-            r: 243,
-            g: 216,
-            b: 63,
-            a: 255,
-        };
-
-        let text_width = unsafe { MeasureText(rl_str!(text), 34) };
-        let newx = posx + (width - text_width) / 2;
-        unsafe { DrawText(rl_str!(text), newx, posy, 34, YELLOW) };
-    }
-
-    fn draw_dialog_box(&mut self, text1: &str, text2: &str, text3: &str, color: Color) {
-        const RWIDTH: i32 = 600;
-        const RHEIGHT: i32 = 200;
-        const RPOSX: i32 = (WORLD_WIDTH - RWIDTH) / 2;
-        const RPOSY: i32 = 100;
-        unsafe {
-            DrawRectangleGradientH(RPOSX, RPOSY, RWIDTH, RHEIGHT, color, color);
-            DrawRectangleLines(RPOSX, RPOSY, RWIDTH, RHEIGHT, color);
+        self.scenes.draw(&self.context);
+        if self.notice_ticks > 0 {
+            let font = self.context.assets.get_font();
+            ui::draw_text_centered_in(
+                font,
+                FRAME_RECT,
+                GAMEPAD_NOTICE_Y,
+                &self.notice,
+                COLOR_WHITE,
+            );
         }
-        self.center_text_at(RPOSX, 150, RWIDTH, text1);
-        self.center_text_at(RPOSX, 190, RWIDTH, text2);
-        self.center_text_at(RPOSX, 230, RWIDTH, text3);
-    }
-
-    fn level_up_draw(&mut self) {
-        self.draw_dialog_box(
-            "CONGRATULATIONS",
-            "YOU DEFEATED THE ALIENS",
-            "PRESS ENTER FOR NEXT LEVEL",
-            GREEN_COLOR,
-        );
-    }
-
-    fn game_over_draw(&mut self) {
-        self.draw_dialog_box(
-            "GAME OVER",
-            "PRESS ENTER TO RESTART",
-            "PRESS ESC TO QUIT",
-            RED_COLOR,
-        );
-    }
-
-    fn game_over(&mut self) {
-        self.state = GameState::GameOver;
-        self.save_high_score();
+        unsafe { EndDrawing() };
     }
 }
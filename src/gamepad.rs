@@ -0,0 +1,228 @@
+// Gamepad input, next to the keyboard.
+//
+// The first gamepad drives the same actions as the keyboard, with a fixed
+// layout: the D-pad or the left stick moves the spaceship and navigates
+// the menus, the bottom face button fires and confirms, the right one goes
+// back and Start pauses. It can be plugged in and out at any time.
+
+use crate::constants::GAMEPAD_DEADZONE;
+use crate::game::PlayerInput;
+use raylib_ffi::enums::{GamepadAxis, GamepadButton};
+use raylib_ffi::{
+    GetGamepadAxisMovement, GetGamepadName, IsGamepadAvailable, IsGamepadButtonDown,
+    IsGamepadButtonPressed,
+};
+use std::ffi::CStr;
+use std::os::raw::c_int;
+
+// Only the first gamepad plays
+const GAMEPAD: c_int = 0;
+
+/// Where the state of the gamepads is read from
+pub trait PadSource {
+    fn is_available(&self, pad: c_int) -> bool;
+    fn get_name(&self, pad: c_int) -> String;
+    // the button is held down
+    fn is_down(&self, pad: c_int, button: GamepadButton) -> bool;
+    // the button went down since the previous frame
+    fn is_pressed(&self, pad: c_int, button: GamepadButton) -> bool;
+    // from -1 to 1
+    fn get_axis(&self, pad: c_int, axis: GamepadAxis) -> f32;
+}
+
+/// The gamepads, as seen by raylib
+pub struct RaylibPads;
+
+impl PadSource for RaylibPads {
+    fn is_available(&self, pad: c_int) -> bool {
+        unsafe { IsGamepadAvailable(pad) }
+    }
+
+    fn get_name(&self, pad: c_int) -> String {
+        let name = unsafe { GetGamepadName(pad) };
+        if name.is_null() {
+            return String::new();
+        }
+        unsafe { CStr::from_ptr(name) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn is_down(&self, pad: c_int, button: GamepadButton) -> bool {
+        unsafe { IsGamepadButtonDown(pad, button as c_int) }
+    }
+
+    fn is_pressed(&self, pad: c_int, button: GamepadButton) -> bool {
+        unsafe { IsGamepadButtonPressed(pad, button as c_int) }
+    }
+
+    fn get_axis(&self, pad: c_int, axis: GamepadAxis) -> f32 {
+        unsafe { GetGamepadAxisMovement(pad, axis as c_int) }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum GamepadEvent {
+    // with the name of the gamepad
+    Connected(String),
+    Disconnected,
+}
+
+// -1, 0 or 1 depending on where the stick is, ignoring the deadzone
+fn stick_direction(value: f32) -> i8 {
+    if value < -GAMEPAD_DEADZONE {
+        -1
+    } else if value > GAMEPAD_DEADZONE {
+        1
+    } else {
+        0
+    }
+}
+
+#[derive(Default)]
+pub struct Gamepad {
+    connected: bool,
+    // the stick directions of the previous frame, to navigate the menus
+    // one step each time the stick is pushed
+    stick_x: i8,
+    stick_y: i8,
+}
+
+impl Gamepad {
+    /// Adds the gamepad actions of the frame to `input` (after the
+    /// keyboard has been read), and tells when the gamepad was plugged in
+    /// or out
+    pub fn poll(
+        &mut self,
+        source: &dyn PadSource,
+        input: &mut PlayerInput,
+    ) -> Option<GamepadEvent> {
+        let available = source.is_available(GAMEPAD);
+        let event = match (self.connected, available) {
+            (false, true) => Some(GamepadEvent::Connected(source.get_name(GAMEPAD))),
+            (true, false) => Some(GamepadEvent::Disconnected),
+            _ => None,
+        };
+        self.connected = available;
+        if !available {
+            self.stick_x = 0;
+            self.stick_y = 0;
+            return event;
+        }
+
+        let down = |button| source.is_down(GAMEPAD, button);
+        let pressed = |button| source.is_pressed(GAMEPAD, button);
+        let x = stick_direction(source.get_axis(GAMEPAD, GamepadAxis::LeftX));
+        let y = stick_direction(source.get_axis(GAMEPAD, GamepadAxis::LeftY));
+        let pushed = |now: i8, before: i8, direction: i8| now == direction && before != direction;
+
+        input.tick.left |= down(GamepadButton::LeftFaceLeft) || x < 0;
+        input.tick.right |= down(GamepadButton::LeftFaceRight) || x > 0;
+        input.tick.fire |= down(GamepadButton::RightFaceDown);
+        input.pause |= pressed(GamepadButton::MiddleRight);
+        input.confirm |= pressed(GamepadButton::RightFaceDown);
+        input.back |= pressed(GamepadButton::RightFaceRight);
+        input.nav_up |= pressed(GamepadButton::LeftFaceUp) || pushed(y, self.stick_y, -1);
+        input.nav_down |= pressed(GamepadButton::LeftFaceDown) || pushed(y, self.stick_y, 1);
+        input.nav_left |= pressed(GamepadButton::LeftFaceLeft) || pushed(x, self.stick_x, -1);
+        input.nav_right |= pressed(GamepadButton::LeftFaceRight) || pushed(x, self.stick_x, 1);
+
+        self.stick_x = x;
+        self.stick_y = y;
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakePad {
+        available: bool,
+        down: Vec<GamepadButton>,
+        pressed: Vec<GamepadButton>,
+        left_x: f32,
+        left_y: f32,
+    }
+
+    impl PadSource for FakePad {
+        fn is_available(&self, _pad: c_int) -> bool {
+            self.available
+        }
+
+        fn get_name(&self, _pad: c_int) -> String {
+            "Fake Pad".to_string()
+        }
+
+        fn is_down(&self, _pad: c_int, button: GamepadButton) -> bool {
+            self.down.contains(&button)
+        }
+
+        fn is_pressed(&self, _pad: c_int, button: GamepadButton) -> bool {
+            self.pressed.contains(&button)
+        }
+
+        fn get_axis(&self, _pad: c_int, axis: GamepadAxis) -> f32 {
+            match axis {
+                GamepadAxis::LeftX => self.left_x,
+                GamepadAxis::LeftY => self.left_y,
+                _ => 0.,
+            }
+        }
+    }
+
+    #[test]
+    fn tells_when_the_gamepad_comes_and_goes() {
+        let mut gamepad = Gamepad::default();
+        let mut input = PlayerInput::default();
+        let mut pad = FakePad::default();
+        assert_eq!(gamepad.poll(&pad, &mut input), None);
+
+        pad.available = true;
+        let event = gamepad.poll(&pad, &mut input);
+        assert_eq!(event, Some(GamepadEvent::Connected("Fake Pad".to_string())));
+        assert_eq!(gamepad.poll(&pad, &mut input), None);
+
+        pad.available = false;
+        let event = gamepad.poll(&pad, &mut input);
+        assert_eq!(event, Some(GamepadEvent::Disconnected));
+    }
+
+    #[test]
+    fn the_stick_moves_outside_of_the_deadzone() {
+        let mut gamepad = Gamepad::default();
+        let mut pad = FakePad {
+            available: true,
+            down: vec![GamepadButton::RightFaceDown],
+            left_x: -GAMEPAD_DEADZONE / 2.,
+            ..FakePad::default()
+        };
+        let mut input = PlayerInput::default();
+        gamepad.poll(&pad, &mut input);
+        assert!(input.tick.fire);
+        assert!(!input.tick.left && !input.tick.right);
+
+        pad.left_x = -0.9;
+        let mut input = PlayerInput::default();
+        gamepad.poll(&pad, &mut input);
+        assert!(input.tick.left && input.tick.fire);
+    }
+
+    #[test]
+    fn holding_the_stick_navigates_one_step() {
+        let mut gamepad = Gamepad::default();
+        let pad = FakePad {
+            available: true,
+            left_y: 1.,
+            ..FakePad::default()
+        };
+        let mut input = PlayerInput::default();
+        gamepad.poll(&pad, &mut input);
+        assert!(input.nav_down);
+
+        let mut input = PlayerInput::default();
+        gamepad.poll(&pad, &mut input);
+        assert!(!input.nav_down);
+    }
+}
@@ -0,0 +1,296 @@
+// The gameplay scene and the overlays shown over the playfield
+
+use crate::constants::*;
+use crate::context::Context;
+use crate::game::PlayerInput;
+use crate::highscores::EnterInitialsScene;
+use crate::input::Action;
+use crate::log;
+use crate::menu::{Menu, MenuItem, PAUSE_MENU};
+use crate::powerup::PowerUpKind;
+use crate::resources::SPACESHIP_TEXTURE;
+use crate::scene::{Scene, Transition};
+use crate::title::{OptionsScene, TitleScene};
+use crate::ui;
+use crate::world::{TickInput, WorldEvent};
+use raylib_ffi::{DrawCircleLinesV, DrawLineEx, DrawRectangleRec, DrawTextureV, Vector2};
+
+/// Runs the simulation and draws the playfield with the HUD
+#[derive(Default)]
+pub struct GameplayScene {
+    input: TickInput,
+}
+
+impl Scene<Context> for GameplayScene {
+    fn handle_input(&mut self, ctx: &mut Context, input: &PlayerInput) -> Transition<Context> {
+        // For debug purposes!!!
+        if input.debug_game_over {
+            log::info("GameOver invoked by keyboard!");
+            ctx.game_over = true;
+            return Transition::Push(Box::new(GameOverScene));
+        }
+        if input.debug_level_up {
+            log::info("LevelUp invoked by keyboard!");
+            return Transition::Push(Box::new(LevelUpScene));
+        }
+
+        self.input = input.tick;
+
+        // the game also pauses by itself when the window loses the focus
+        match input.pause || input.focus_lost {
+            true => Transition::Push(Box::new(PauseScene::default())),
+            false => Transition::None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> Transition<Context> {
+        ctx.assets.update_music();
+
+        let mut transition = Transition::None;
+        for event in ctx.world.step(&self.input) {
+            match event {
+                WorldEvent::LaserFired => ctx.assets.play_laser_sound(),
+                WorldEvent::FormationStep { note } => ctx.assets.play_alien_step_sound(note),
+                WorldEvent::AlienDestroyed => ctx.assets.play_alien_explosion_sound(),
+                WorldEvent::MysteryShipDestroyed => ctx.assets.play_mystery_explosion_sound(),
+                WorldEvent::SpaceshipHit => ctx.assets.play_ship_explosion_sound(),
+                WorldEvent::PowerUpCollected => ctx.assets.play_powerup_sound(),
+                WorldEvent::WaveCleared => transition = Transition::Push(Box::new(LevelUpScene)),
+                WorldEvent::GameOver => transition = game_over(ctx),
+            }
+        }
+
+        if ctx.world.mysteryship.is_active() {
+            ctx.assets.play_mystery_sound();
+        }
+        transition
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let font = ctx.assets.get_font();
+        let world = &ctx.world;
+        unsafe {
+            DrawLineEx(
+                Vector2 {
+                    x: GUI_LINE_X1,
+                    y: GUI_LINE_Y,
+                },
+                Vector2 {
+                    x: GUI_LINE_X2,
+                    y: GUI_LINE_Y,
+                },
+                GUI_LINE_THICKNESS,
+                FRAME_COLOR,
+            );
+        }
+        let level = match (ctx.game_over, world.invaded) {
+            (false, _) => format!("LEVEL {:0>2}", world.level),
+            (true, false) => "GAME OVER".to_string(),
+            (true, true) => "INVADED".to_string(),
+        };
+        ui::draw_text(font, &level, LEVEL_POS, FRAME_COLOR);
+        ui::draw_text(font, "SCORE", GUI_SCORE_TEXT_POS, FRAME_COLOR);
+        ui::draw_text(
+            font,
+            &format!("{:0>5}", world.score),
+            GUI_SCORE_VALUE_POS,
+            FRAME_COLOR,
+        );
+        ui::draw_text(font, "HIGH SCORE", GUI_HIGH_SCORE_TEXT_POS, FRAME_COLOR);
+        ui::draw_text(
+            font,
+            &format!("{:0>5}", ctx.high_score()),
+            GUI_HIGH_SCORE_VALUE_POS,
+            FRAME_COLOR,
+        );
+
+        // the timed power-ups, with the seconds they have left
+        let mut pos = GUI_POWERUP_POS;
+        for effect in world.effects.iter() {
+            let text = format!("{}{}", effect.kind.get_letter(), effect.get_seconds_left());
+            ui::draw_text(font, &text, pos, effect.kind.get_color());
+            pos.x += GUI_POWERUP_INC;
+        }
+
+        // DRAW OTHER OBJECTS
+
+        let mut x = GUI_LIVEIMG_X;
+        for _ in 0..world.lives {
+            unsafe {
+                DrawTextureV(
+                    ctx.resources.get_texture(SPACESHIP_TEXTURE),
+                    Vector2 {
+                        x,
+                        y: GUI_LIVEIMG_Y,
+                    },
+                    COLOR_WHITE,
+                );
+            }
+            x += GUI_LIVEIMG_INC;
+        }
+
+        for obstacle in world.obstacles.iter() {
+            obstacle.draw();
+        }
+        world.spaceship.draw(&ctx.resources);
+        if world.has_effect(PowerUpKind::Shield) {
+            let ship = world.spaceship.get_rect();
+            let center = Vector2 {
+                x: ship.x + ship.width / 2.,
+                y: ship.y + ship.height / 2.,
+            };
+            unsafe { DrawCircleLinesV(center, ship.width * 0.7, SHIELD_COLOR) };
+        }
+        for laser in world.lasers.iter() {
+            laser.draw();
+        }
+
+        for alien in world.aliens.iter() {
+            alien.draw(&ctx.resources);
+        }
+        for explosion in world.explosions.iter() {
+            explosion.draw(&ctx.resources);
+        }
+        for powerup in world.powerups.iter() {
+            powerup.draw(font);
+        }
+        for popup in world.popups.iter() {
+            popup.draw(font);
+        }
+
+        for laser in world.alien_lasers.iter() {
+            laser.draw();
+        }
+
+        world.mysteryship.draw(&ctx.resources);
+    }
+}
+
+// Ends the game: the player enters their initials first if the score
+// makes it into the leaderboard
+fn game_over(ctx: &mut Context) -> Transition<Context> {
+    ctx.game_over = true;
+    match ctx.leaderboard.qualifies(ctx.world.score) {
+        true => Transition::Push(Box::new(EnterInitialsScene::default())),
+        false => Transition::Push(Box::new(GameOverScene)),
+    }
+}
+
+/// Freezes the game and shows the pause menu over the playfield
+pub struct PauseScene {
+    menu: Menu,
+}
+
+impl Default for PauseScene {
+    fn default() -> Self {
+        PauseScene {
+            menu: Menu::new(PAUSE_MENU),
+        }
+    }
+}
+
+impl Scene<Context> for PauseScene {
+    fn handle_input(&mut self, ctx: &mut Context, input: &PlayerInput) -> Transition<Context> {
+        if input.pause || input.back {
+            return Transition::Pop;
+        }
+        if input.nav_up {
+            self.menu.select_previous();
+        }
+        if input.nav_down {
+            self.menu.select_next();
+        }
+        if !input.confirm {
+            return Transition::None;
+        }
+        match self.menu.get_selected() {
+            MenuItem::Resume => Transition::Pop,
+            MenuItem::RestartLevel => {
+                ctx.world.restart_level();
+                Transition::Pop
+            }
+            MenuItem::Options => Transition::Push(Box::new(OptionsScene::default())),
+            MenuItem::QuitToMenu => {
+                ctx.quit_game();
+                Transition::ReplaceAll(Box::new(TitleScene::default()))
+            }
+            _ => Transition::None,
+        }
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let font = ctx.assets.get_font();
+        unsafe { DrawRectangleRec(FRAME_RECT, PAUSE_SHADE_COLOR) };
+        ui::draw_text_centered_in(font, FRAME_RECT, PAUSE_TITLE_Y, "PAUSED", FRAME_COLOR);
+        ui::draw_menu(font, FRAME_RECT, PAUSE_TITLE_Y + 70., &self.menu);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+pub struct LevelUpScene;
+
+impl Scene<Context> for LevelUpScene {
+    fn handle_input(&mut self, ctx: &mut Context, input: &PlayerInput) -> Transition<Context> {
+        if !input.confirm {
+            return Transition::None;
+        }
+        ctx.world.init_level();
+        Transition::Pop
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let prompt = format!(
+            "PRESS {} FOR NEXT LEVEL",
+            ctx.actions.get_key_label(Action::Confirm)
+        );
+        ui::draw_dialog_box(
+            "CONGRATULATIONS",
+            "YOU DEFEATED THE ALIENS",
+            &prompt,
+            GREEN_COLOR,
+        );
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+pub struct GameOverScene;
+
+impl Scene<Context> for GameOverScene {
+    fn handle_input(&mut self, ctx: &mut Context, input: &PlayerInput) -> Transition<Context> {
+        if input.back {
+            ctx.quit_game();
+            return Transition::ReplaceAll(Box::new(TitleScene::default()));
+        }
+        if input.confirm {
+            ctx.init_game();
+            return Transition::ReplaceAll(Box::new(GameplayScene::default()));
+        }
+        Transition::None
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let title = match ctx.world.invaded {
+            true => "INVADED",
+            false => "GAME OVER",
+        };
+        let restart = format!(
+            "PRESS {} TO RESTART",
+            ctx.actions.get_key_label(Action::Confirm)
+        );
+        let menu = format!(
+            "PRESS {} FOR THE MENU",
+            ctx.actions.get_key_label(Action::Back)
+        );
+        ui::draw_dialog_box(title, &restart, &menu, RED_COLOR);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,112 @@
+// Whole games played without a window.
+//
+// `spaceinv --headless <games>` steps seeded games of the World with no
+// raylib window, as fast as the machine goes, and prints how each one
+// ended. The player is a simple bot that follows the lowest alien and
+// keeps firing, so the same seed always plays the same game: it is meant
+// for checking the simulation in bulk (e.g. thousands of games in CI).
+
+use crate::clock::TICK_RATE;
+use crate::config::Config;
+use crate::wave::Wave;
+use crate::world::{TickInput, World, WorldEvent};
+
+// A game that lasts longer than this (in simulated time) is stopped
+const MAX_GAME_SECONDS: u32 = 30 * 60;
+
+/// How a headless game ended
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameSummary {
+    pub seed: u64,
+    pub score: usize,
+    pub level: usize,
+    pub lives: usize,
+    pub ticks: u32,
+    // the game was stopped before it was over
+    pub timed_out: bool,
+}
+
+// Moves the spaceship under the lowest alien, firing all the time
+fn bot_input(world: &World) -> TickInput {
+    let ship = world.spaceship.get_rect();
+    let ship_x = ship.x + ship.width / 2.;
+    let target = world
+        .aliens
+        .iter()
+        .map(|alien| alien.get_rect())
+        .max_by(|a, b| a.y.total_cmp(&b.y))
+        .map(|rect| rect.x + rect.width / 2.);
+    TickInput {
+        left: target.is_some_and(|x| x < ship_x - ship.width / 4.),
+        right: target.is_some_and(|x| x > ship_x + ship.width / 4.),
+        fire: true,
+    }
+}
+
+/// Plays a game from `seed` until it is over (or stopped after
+/// MAX_GAME_SECONDS), going on to the next level whenever a wave is cleared
+pub fn play_game(seed: u64, config: &Config, waves: Vec<Wave>) -> GameSummary {
+    let mut world = World::new(seed, config, waves);
+    let max_ticks = MAX_GAME_SECONDS * TICK_RATE;
+    let mut ticks = 0;
+    let mut over = false;
+    while !over && ticks < max_ticks {
+        let input = bot_input(&world);
+        ticks += 1;
+        for event in world.step(&input) {
+            match event {
+                WorldEvent::GameOver => over = true,
+                WorldEvent::WaveCleared => world.init_level(),
+                _ => (),
+            }
+        }
+    }
+    GameSummary {
+        seed,
+        score: world.score,
+        level: world.level,
+        lives: world.lives,
+        ticks,
+        timed_out: !over,
+    }
+}
+
+/// Plays `games` games with the seeds following `first_seed`, printing one
+/// line per game and the average score
+pub fn run(games: u32, first_seed: u64, config: &Config, waves: &[Wave]) {
+    let mut total_score = 0;
+    for game in 0..games {
+        let seed = first_seed.wrapping_add(game as u64);
+        let summary = play_game(seed, config, waves.to_vec());
+        let stopped = match summary.timed_out {
+            true => " (timed out)",
+            false => "",
+        };
+        println!(
+            "seed {} score {} level {} lives {} ticks {}{}",
+            summary.seed, summary.score, summary.level, summary.lives, summary.ticks, stopped
+        );
+        total_score += summary.score;
+    }
+    if games > 0 {
+        println!(
+            "{} games, average score {}",
+            games,
+            total_score / games as usize
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headless_games_end_and_replay_the_same() {
+        let config = Config::default();
+        let summary = play_game(3, &config, Vec::new());
+        assert!(!summary.timed_out);
+        assert!(summary.score > 0);
+        assert_eq!(play_game(3, &config, Vec::new()), summary);
+    }
+}
@@ -0,0 +1,152 @@
+// Entering the initials after a good run, and the leaderboard screen
+
+use crate::constants::*;
+use crate::context::Context;
+use crate::game::PlayerInput;
+use crate::gameplay::GameOverScene;
+use crate::input::Action;
+use crate::leaderboard::INITIALS_LEN;
+use crate::scene::{Scene, Transition};
+use crate::ui;
+use raylib_ffi::{DrawRectangleLinesEx, DrawRectangleRec, Vector2};
+
+pub struct EnterInitialsScene {
+    initials: [u8; INITIALS_LEN],
+    cursor: usize,
+}
+
+impl Default for EnterInitialsScene {
+    fn default() -> Self {
+        EnterInitialsScene {
+            initials: [b'A'; INITIALS_LEN],
+            cursor: 0,
+        }
+    }
+}
+
+impl Scene<Context> for EnterInitialsScene {
+    fn handle_input(&mut self, ctx: &mut Context, input: &PlayerInput) -> Transition<Context> {
+        let letter = &mut self.initials[self.cursor];
+        if input.nav_up {
+            *letter = if *letter == b'Z' { b'A' } else { *letter + 1 };
+        }
+        if input.nav_down {
+            *letter = if *letter == b'A' { b'Z' } else { *letter - 1 };
+        }
+        if input.nav_left && self.cursor > 0 {
+            self.cursor -= 1;
+        }
+        if input.nav_right && self.cursor < INITIALS_LEN - 1 {
+            self.cursor += 1;
+        }
+        if !input.confirm {
+            return Transition::None;
+        }
+        let rank = ctx.save_run(&self.initials);
+        Transition::Replace(Box::new(LeaderboardScene::after_run(rank)))
+    }
+
+    fn draw(&self, ctx: &Context) {
+        // the letter being edited is shown between brackets
+        let initials: Vec<String> = self
+            .initials
+            .iter()
+            .enumerate()
+            .map(|(i, letter)| match i == self.cursor {
+                true => format!("[{}]", *letter as char),
+                false => format!(" {} ", *letter as char),
+            })
+            .collect();
+        // the arrows navigate whatever the bindings are
+        let prompt = format!(
+            "USE ARROWS, THEN {}",
+            ctx.actions.get_key_label(Action::Confirm)
+        );
+        ui::draw_dialog_box("NEW HIGH SCORE!", &initials.join(""), &prompt, GREEN_COLOR);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+pub struct LeaderboardScene {
+    // the run that was just added, if any
+    highlighted_rank: Option<usize>,
+    // shown over the playfield at the end of a game (rather than from
+    // the title screen)
+    after_run: bool,
+}
+
+impl LeaderboardScene {
+    pub fn new() -> Self {
+        LeaderboardScene {
+            highlighted_rank: None,
+            after_run: false,
+        }
+    }
+
+    pub fn after_run(highlighted_rank: Option<usize>) -> Self {
+        LeaderboardScene {
+            highlighted_rank,
+            after_run: true,
+        }
+    }
+}
+
+impl Scene<Context> for LeaderboardScene {
+    fn handle_input(&mut self, _ctx: &mut Context, input: &PlayerInput) -> Transition<Context> {
+        if !(input.confirm || input.back) {
+            return Transition::None;
+        }
+        match self.after_run {
+            true => Transition::Replace(Box::new(GameOverScene)),
+            false => Transition::Pop,
+        }
+    }
+
+    fn draw(&self, ctx: &Context) {
+        const ROW_HEIGHT: f32 = 38.;
+        let font = ctx.assets.get_font();
+        let rect = LEADERBOARD_RECT;
+        unsafe {
+            DrawRectangleRec(rect, WINDOW_BKG_COLOR);
+            DrawRectangleLinesEx(rect, FRAME_THICKNESS, FRAME_COLOR);
+        }
+        let mut y = rect.y + 20.;
+        ui::draw_text_centered_in(font, rect, y, "HIGH SCORES", FRAME_COLOR);
+        y += ROW_HEIGHT * 1.5;
+
+        let entries = ctx.leaderboard.entries();
+        for (rank, entry) in entries.iter().enumerate() {
+            let row = format!(
+                "{:>2}. {}  {:>6}  L{:0>2}  {}",
+                rank + 1,
+                entry.initials,
+                entry.score,
+                entry.level,
+                entry.date
+            );
+            let color = match self.highlighted_rank == Some(rank) {
+                true => COLOR_WHITE,
+                false => FRAME_COLOR,
+            };
+            ui::draw_text(font, &row, Vector2 { x: rect.x + 40., y }, color);
+            y += ROW_HEIGHT;
+        }
+        if entries.is_empty() {
+            ui::draw_text_centered_in(font, rect, y, "NO SCORES YET", FRAME_COLOR);
+        }
+
+        let y = rect.y + rect.height - ROW_HEIGHT - 10.;
+        let prompt = format!(
+            "PRESS {} TO CONTINUE",
+            ctx.actions.get_key_label(Action::Confirm)
+        );
+        ui::draw_text_centered_in(font, rect, y, &prompt, FRAME_COLOR);
+    }
+
+    fn is_overlay(&self) -> bool {
+        self.after_run
+    }
+}
@@ -0,0 +1,477 @@
+// The action-mapping layer.
+//
+// The game never asks for a key directly: it polls actions, and an action
+// is on when any key bound to it is. Every action is read on its own, so
+// any of them can be held together (moving while firing, for instance).
+//
+// The player can bind the gameplay actions to other keys from the options
+// screen. The bindings are saved in the data directory, a version header
+// followed by one action per line:
+//
+//     spaceinv-controls 1
+//     move_left A
+//     fire LEFT_CTRL
+
+use crate::game::PlayerInput;
+use crate::storage;
+use crate::world::TickInput;
+use raylib_ffi::enums::KeyboardKey;
+use raylib_ffi::{GetKeyPressed, IsKeyDown, IsKeyPressed};
+use std::os::raw::c_int;
+use std::path::Path;
+
+pub const CONTROLS_FILE: &str = "controls.txt";
+const HEADER: &str = "spaceinv-controls";
+const VERSION: u32 = 1;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Pause,
+    Confirm,
+    Back,
+    NavUp,
+    NavDown,
+    NavLeft,
+    NavRight,
+    DebugGameOver,
+    DebugLevelUp,
+}
+
+/// The actions the player can bind to other keys (the menu navigation and
+/// the debug keys are fixed)
+pub const REBINDABLE_ACTIONS: [Action; 6] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Fire,
+    Action::Pause,
+    Action::Confirm,
+    Action::Back,
+];
+
+impl Action {
+    // The name of the action in the controls file
+    fn get_id(&self) -> &'static str {
+        match self {
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::Fire => "fire",
+            Action::Pause => "pause",
+            Action::Confirm => "confirm",
+            Action::Back => "back",
+            Action::NavUp => "nav_up",
+            Action::NavDown => "nav_down",
+            Action::NavLeft => "nav_left",
+            Action::NavRight => "nav_right",
+            Action::DebugGameOver => "debug_game_over",
+            Action::DebugLevelUp => "debug_level_up",
+        }
+    }
+
+    pub fn get_label(&self) -> String {
+        self.get_id().replace('_', " ").to_uppercase()
+    }
+
+    // The arrows keep navigating the menus whatever the gameplay keys are
+    fn is_navigation(&self) -> bool {
+        matches!(
+            self,
+            Action::NavUp | Action::NavDown | Action::NavLeft | Action::NavRight
+        )
+    }
+}
+
+const KEY_NAMES: [(KeyboardKey, &str); 31] = [
+    (KeyboardKey::Apostrophe, "'"),
+    (KeyboardKey::Comma, ","),
+    (KeyboardKey::Minus, "-"),
+    (KeyboardKey::Period, "."),
+    (KeyboardKey::Slash, "/"),
+    (KeyboardKey::Semicolon, ";"),
+    (KeyboardKey::Equal, "="),
+    (KeyboardKey::LeftBracket, "["),
+    (KeyboardKey::Backslash, "\\"),
+    (KeyboardKey::RightBracket, "]"),
+    (KeyboardKey::Grave, "`"),
+    (KeyboardKey::Space, "SPACE"),
+    (KeyboardKey::Escape, "ESCAPE"),
+    (KeyboardKey::Enter, "ENTER"),
+    (KeyboardKey::Tab, "TAB"),
+    (KeyboardKey::Backspace, "BACKSPACE"),
+    (KeyboardKey::Insert, "INSERT"),
+    (KeyboardKey::Delete, "DELETE"),
+    (KeyboardKey::Right, "RIGHT"),
+    (KeyboardKey::Left, "LEFT"),
+    (KeyboardKey::Down, "DOWN"),
+    (KeyboardKey::Up, "UP"),
+    (KeyboardKey::PageUp, "PAGE_UP"),
+    (KeyboardKey::PageDown, "PAGE_DOWN"),
+    (KeyboardKey::Home, "HOME"),
+    (KeyboardKey::End, "END"),
+    (KeyboardKey::LeftShift, "LEFT_SHIFT"),
+    (KeyboardKey::LeftControl, "LEFT_CTRL"),
+    (KeyboardKey::LeftAlt, "LEFT_ALT"),
+    (KeyboardKey::RightShift, "RIGHT_SHIFT"),
+    (KeyboardKey::RightControl, "RIGHT_CTRL"),
+];
+
+fn key_in(key: c_int, first: KeyboardKey, last: KeyboardKey) -> bool {
+    (first as c_int..=last as c_int).contains(&key)
+}
+
+/// The name of a key, in the controls file and on screen (None for the
+/// keys that can't be bound)
+pub fn key_name(key: c_int) -> Option<String> {
+    if key_in(key, KeyboardKey::A, KeyboardKey::Z)
+        || key_in(key, KeyboardKey::Zero, KeyboardKey::Nine)
+    {
+        return char::from_u32(key as u32).map(String::from);
+    }
+    if key_in(key, KeyboardKey::F1, KeyboardKey::F12) {
+        return Some(format!("F{}", key - KeyboardKey::F1 as c_int + 1));
+    }
+    KEY_NAMES
+        .iter()
+        .find(|(named, _)| *named as c_int == key)
+        .map(|(_, name)| name.to_string())
+}
+
+fn key_from_name(name: &str) -> Option<c_int> {
+    // every key code that can be bound is below the right control key
+    (0..=KeyboardKey::RightControl as c_int).find(|key| key_name(*key).as_deref() == Some(name))
+}
+
+/// Where the state of the keys is read from
+pub trait KeySource {
+    // the key is held down
+    fn is_down(&self, key: c_int) -> bool;
+    // the key went down since the previous frame
+    fn is_pressed(&self, key: c_int) -> bool;
+    // the next key pressed since the previous frame, if any
+    fn get_pressed_key(&self) -> Option<c_int>;
+}
+
+/// The keyboard, as seen by raylib
+pub struct RaylibKeys;
+
+impl KeySource for RaylibKeys {
+    fn is_down(&self, key: c_int) -> bool {
+        unsafe { IsKeyDown(key) }
+    }
+
+    fn is_pressed(&self, key: c_int) -> bool {
+        unsafe { IsKeyPressed(key) }
+    }
+
+    fn get_pressed_key(&self) -> Option<c_int> {
+        match unsafe { GetKeyPressed() } {
+            0 => None,
+            key => Some(key),
+        }
+    }
+}
+
+/// The keys bound to the actions (a key can drive several actions, e.g.
+/// Left both moves the spaceship and navigates the menus)
+#[derive(Clone, Debug)]
+pub struct ActionMap {
+    bindings: Vec<(Action, c_int)>,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        let bindings = [
+            (Action::MoveLeft, KeyboardKey::Left),
+            (Action::MoveRight, KeyboardKey::Right),
+            (Action::Fire, KeyboardKey::Space),
+            (Action::Pause, KeyboardKey::P),
+            (Action::Confirm, KeyboardKey::Enter),
+            (Action::Back, KeyboardKey::Escape),
+            (Action::NavUp, KeyboardKey::Up),
+            (Action::NavDown, KeyboardKey::Down),
+            (Action::NavLeft, KeyboardKey::Left),
+            (Action::NavRight, KeyboardKey::Right),
+            (Action::DebugGameOver, KeyboardKey::G),
+            (Action::DebugLevelUp, KeyboardKey::L),
+        ];
+        ActionMap {
+            bindings: bindings
+                .iter()
+                .map(|(action, key)| (*action, *key as c_int))
+                .collect(),
+        }
+    }
+}
+
+impl ActionMap {
+    fn keys_of(&self, action: Action) -> impl Iterator<Item = c_int> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(bound, _)| *bound == action)
+            .map(|(_, key)| *key)
+    }
+
+    /// The key shown for an action (its first one)
+    pub fn get_key(&self, action: Action) -> Option<c_int> {
+        self.keys_of(action).next()
+    }
+
+    /// The name of the key shown for an action, as written on the screen
+    pub fn get_key_label(&self, action: Action) -> String {
+        self.get_key(action)
+            .and_then(key_name)
+            .unwrap_or_default()
+            .replace('_', " ")
+    }
+
+    /// Binds `key` to `action` alone. An action that loses its only key
+    /// to it gets the previous key of `action` instead (the two are
+    /// swapped); the menu navigation keeps its own.
+    pub fn rebind(&mut self, action: Action, key: c_int) {
+        let previous = self.get_key(action);
+        let displaced: Vec<Action> = self
+            .bindings
+            .iter()
+            .filter(|(bound, bound_key)| {
+                *bound != action && *bound_key == key && !bound.is_navigation()
+            })
+            .map(|(bound, _)| *bound)
+            .collect();
+        self.bindings.retain(|(bound, bound_key)| {
+            *bound != action && !(*bound_key == key && displaced.contains(bound))
+        });
+        self.bindings.push((action, key));
+
+        for other in displaced {
+            if REBINDABLE_ACTIONS.contains(&other) && self.get_key(other).is_none() {
+                if let Some(previous) = previous {
+                    self.bindings.push((other, previous));
+                }
+            }
+        }
+    }
+
+    /// Reads saved bindings, on top of the default ones
+    pub fn parse(text: &str) -> Result<ActionMap, String> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, line)) if line.strip_prefix(HEADER).is_some() => {
+                let version = line[HEADER.len()..].trim();
+                if version.parse::<u32>() != Ok(VERSION) {
+                    return Err(format!("unsupported version '{}'", version));
+                }
+            }
+            _ => return Err("not a controls file".to_string()),
+        }
+
+        let mut actions = ActionMap::default();
+        for (index, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let error = |msg: String| format!("line {}: {}", index + 1, msg);
+            let mut fields = line.split_whitespace();
+            let (id, name) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(id), Some(name), None) => (id, name),
+                _ => return Err(error(format!("invalid binding '{}'", line))),
+            };
+            let action = REBINDABLE_ACTIONS
+                .into_iter()
+                .find(|action| action.get_id() == id)
+                .ok_or_else(|| error(format!("unknown action '{}'", id)))?;
+            let key =
+                key_from_name(name).ok_or_else(|| error(format!("unknown key '{}'", name)))?;
+            actions.rebind(action, key);
+        }
+        Ok(actions)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut text = format!("{} {}\n", HEADER, VERSION);
+        for action in REBINDABLE_ACTIONS {
+            if let Some(name) = self.get_key(action).and_then(key_name) {
+                text += &format!("{} {}\n", action.get_id(), name);
+            }
+        }
+        storage::write_atomic(path, &text)
+    }
+
+    /// Loads the bindings, falling back to the default ones when the file
+    /// is missing or can't be read (see storage::load_or_recover)
+    pub fn load_or_recover(path: &Path) -> ActionMap {
+        storage::load_or_recover(path, ActionMap::parse, ActionMap::default)
+    }
+
+    pub fn is_held(&self, source: &dyn KeySource, action: Action) -> bool {
+        self.keys_of(action).any(|key| source.is_down(key))
+    }
+
+    pub fn is_pressed(&self, source: &dyn KeySource, action: Action) -> bool {
+        self.keys_of(action).any(|key| source.is_pressed(key))
+    }
+
+    /// Reads the actions of the frame into `input`: the held ones make the
+    /// new tick input, the pressed ones add up with those not consumed yet
+    pub fn poll(&self, source: &dyn KeySource, input: &mut PlayerInput) {
+        input.tick = TickInput {
+            left: self.is_held(source, Action::MoveLeft),
+            right: self.is_held(source, Action::MoveRight),
+            fire: self.is_held(source, Action::Fire),
+        };
+        let pressed = |action| self.is_pressed(source, action);
+        input.pause |= pressed(Action::Pause);
+        input.confirm |= pressed(Action::Confirm);
+        input.back |= pressed(Action::Back);
+        input.nav_up |= pressed(Action::NavUp);
+        input.nav_down |= pressed(Action::NavDown);
+        input.nav_left |= pressed(Action::NavLeft);
+        input.nav_right |= pressed(Action::NavRight);
+        input.debug_game_over |= pressed(Action::DebugGameOver);
+        input.debug_level_up |= pressed(Action::DebugLevelUp);
+        input.key = input.key.or_else(|| source.get_pressed_key());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::storage::tests::test_dir;
+    use crate::world::World;
+
+    #[derive(Default)]
+    struct FakeKeys {
+        down: Vec<KeyboardKey>,
+        pressed: Vec<KeyboardKey>,
+    }
+
+    impl KeySource for FakeKeys {
+        fn is_down(&self, key: c_int) -> bool {
+            self.down.iter().any(|down| *down as c_int == key)
+        }
+
+        fn is_pressed(&self, key: c_int) -> bool {
+            self.pressed.iter().any(|pressed| *pressed as c_int == key)
+        }
+
+        fn get_pressed_key(&self) -> Option<c_int> {
+            self.pressed.first().map(|key| *key as c_int)
+        }
+    }
+
+    #[test]
+    fn moving_and_firing_at_the_same_time() {
+        let keys = FakeKeys {
+            down: vec![KeyboardKey::Left, KeyboardKey::Space],
+            ..FakeKeys::default()
+        };
+        let mut input = PlayerInput::default();
+        ActionMap::default().poll(&keys, &mut input);
+        assert!(input.tick.left && input.tick.fire);
+
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.aliens.clear();
+        let start_x = world.spaceship.get_rect().x;
+        // the gun is ready after its first cooldown
+        for _ in 0..30 {
+            world.step(&input.tick);
+        }
+        assert!(world.spaceship.get_rect().x < start_x);
+        assert!(!world.lasers.is_empty());
+    }
+
+    #[test]
+    fn pressed_actions_add_up_until_consumed() {
+        let actions = ActionMap::default();
+        let mut input = PlayerInput::default();
+        let keys = FakeKeys {
+            pressed: vec![KeyboardKey::Enter],
+            ..FakeKeys::default()
+        };
+        actions.poll(&keys, &mut input);
+        actions.poll(&FakeKeys::default(), &mut input);
+
+        assert!(input.confirm);
+        assert!(!input.pause);
+    }
+
+    #[test]
+    fn rebinding_swaps_the_keys_of_the_actions() {
+        let mut actions = ActionMap::default();
+        actions.rebind(Action::MoveLeft, KeyboardKey::A as c_int);
+        assert_eq!(
+            actions.get_key(Action::MoveLeft),
+            Some(KeyboardKey::A as c_int)
+        );
+
+        // A was on move left, which had Left before
+        actions.rebind(Action::Fire, KeyboardKey::A as c_int);
+        assert_eq!(actions.get_key(Action::Fire), Some(KeyboardKey::A as c_int));
+        assert_eq!(
+            actions.get_key(Action::MoveLeft),
+            Some(KeyboardKey::Space as c_int)
+        );
+
+        // the arrows still navigate the menus, the debug keys give way
+        actions.rebind(Action::Pause, KeyboardKey::Up as c_int);
+        actions.rebind(Action::Confirm, KeyboardKey::G as c_int);
+        let keys = FakeKeys {
+            pressed: vec![KeyboardKey::Up, KeyboardKey::G],
+            ..FakeKeys::default()
+        };
+        let mut input = PlayerInput::default();
+        actions.poll(&keys, &mut input);
+        assert!(input.pause && input.nav_up && input.confirm);
+        assert!(!input.debug_game_over);
+    }
+
+    #[test]
+    fn bindings_are_saved_and_loaded_back() {
+        let path = test_dir("controls-save").join(CONTROLS_FILE);
+        let mut actions = ActionMap::default();
+        actions.rebind(Action::Fire, KeyboardKey::LeftControl as c_int);
+        actions.rebind(Action::MoveRight, KeyboardKey::F5 as c_int);
+        actions.save(&path).unwrap();
+
+        let loaded = ActionMap::load_or_recover(&path);
+        for action in REBINDABLE_ACTIONS {
+            assert_eq!(loaded.get_key(action), actions.get_key(action));
+        }
+    }
+
+    #[test]
+    fn missing_or_corrupt_files_mean_the_default_bindings() {
+        let dir = test_dir("controls-load");
+        let actions = ActionMap::load_or_recover(&dir.join(CONTROLS_FILE));
+        assert_eq!(
+            actions.get_key(Action::Fire),
+            Some(KeyboardKey::Space as c_int)
+        );
+
+        let err = ActionMap::parse("spaceinv-controls 1\nfire SPACE\njump W\n").unwrap_err();
+        assert_eq!(err, "line 3: unknown action 'jump'");
+        let err = ActionMap::parse("spaceinv-controls 1\nfire NOPE\n").unwrap_err();
+        assert_eq!(err, "line 2: unknown key 'NOPE'");
+
+        let path = dir.join("corrupt.txt");
+        std::fs::write(&path, "garbage").unwrap();
+        let actions = ActionMap::load_or_recover(&path);
+        assert_eq!(
+            actions.get_key(Action::Back),
+            Some(KeyboardKey::Escape as c_int)
+        );
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn names_every_bindable_key() {
+        assert_eq!(key_name(KeyboardKey::Q as c_int).as_deref(), Some("Q"));
+        assert_eq!(key_name(KeyboardKey::F12 as c_int).as_deref(), Some("F12"));
+        assert_eq!(key_name(KeyboardKey::Null as c_int), None);
+        for (key, name) in KEY_NAMES {
+            assert_eq!(key_from_name(name), Some(key as c_int));
+        }
+    }
+}
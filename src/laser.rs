@@ -2,19 +2,106 @@ use crate::constants::*;
 use ray::{Rectangle, Vector2};
 use raylib_ffi as ray;
 
+// The shot of the spaceship and the three kinds of alien projectiles
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LaserKind {
+    Player,
+    // a player laser that goes through the aliens
+    Piercing,
+    // fast, used for the aimed shots
+    Rolling,
+    // the standard straight shot
+    Plunger,
+    // slow, zig-zags on its way down
+    Squiggly,
+    // dropped by the bomber mystery ship
+    Bomb,
+}
+
+impl LaserKind {
+    pub fn get_size(&self) -> Vector2 {
+        match self {
+            LaserKind::Player | LaserKind::Piercing => LASER_SIZE,
+            LaserKind::Rolling => Vector2 { x: 5., y: 14. },
+            LaserKind::Plunger => Vector2 { x: 7., y: 14. },
+            LaserKind::Squiggly => Vector2 { x: 6., y: 14. },
+            LaserKind::Bomb => Vector2 { x: 9., y: 9. },
+        }
+    }
+
+    // Index of an alien projectile in the per-kind config values
+    pub fn get_alien_index(&self) -> Option<usize> {
+        match self {
+            LaserKind::Player | LaserKind::Piercing | LaserKind::Bomb => None,
+            LaserKind::Rolling => Some(0),
+            LaserKind::Plunger => Some(1),
+            LaserKind::Squiggly => Some(2),
+        }
+    }
+
+    // Multiplies the configured alien laser speed
+    pub fn get_speed_factor(&self) -> f32 {
+        match self {
+            LaserKind::Player | LaserKind::Piercing | LaserKind::Plunger => 1.,
+            LaserKind::Rolling => 1.5,
+            LaserKind::Squiggly => 0.6,
+            LaserKind::Bomb => 0.8,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Laser {
+    kind: LaserKind,
     position: Vector2,
     speed: f32,
     active: bool,
+    // ticks since the shot was fired, drives the animation and the zig-zag
+    age: u32,
 }
 
 impl Laser {
     pub fn new(position: Vector2, speed: f32) -> Self {
         Laser {
+            kind: LaserKind::Player,
             position,
             speed,
             active: true,
+            age: 0,
+        }
+    }
+
+    pub fn piercing(position: Vector2, speed: f32) -> Self {
+        Laser {
+            kind: LaserKind::Piercing,
+            ..Laser::new(position, speed)
+        }
+    }
+
+    /// An alien projectile fired downwards, centered on `position`
+    pub fn alien(kind: LaserKind, position: Vector2, speed: f32) -> Self {
+        let size = kind.get_size();
+        Laser {
+            kind,
+            position: Vector2 {
+                x: position.x - size.x / 2.,
+                y: position.y,
+            },
+            speed: speed * kind.get_speed_factor(),
+            active: true,
+            age: 0,
+        }
+    }
+
+    pub fn get_kind(&self) -> LaserKind {
+        self.kind
+    }
+
+    pub fn get_center(&self) -> Vector2 {
+        let rect = self.get_rect();
+        Vector2 {
+            x: rect.x + rect.width / 2.,
+            y: rect.y + rect.height / 2.,
         }
     }
 
@@ -28,25 +115,148 @@ impl Laser {
 
     pub fn update(&mut self) {
         if self.active {
+            self.age += 1;
             self.position.y += self.speed;
+            if self.kind == LaserKind::Squiggly {
+                self.position.x += match (self.age / 8) % 2 {
+                    0 => 1.,
+                    _ => -1.,
+                };
+            }
             if self.position.y > (WORLD_HEIGHT - OFFSETY) as f32 || self.position.y < 25. {
                 self.active = false;
             }
         }
     }
 
+    // Flips every few ticks
+    fn get_frame(&self) -> u32 {
+        (self.age / 4) % 2
+    }
+
     pub fn draw(&self) {
+        let rect = self.get_rect();
+        let (x, y) = (rect.x, rect.y);
+        let frame = self.get_frame() as f32;
         unsafe {
-            ray::DrawRectangleV(self.position, LASER_SIZE, LASER_COLOR);
+            match self.kind {
+                LaserKind::Player => ray::DrawRectangleV(self.position, LASER_SIZE, LASER_COLOR),
+                LaserKind::Piercing => ray::DrawRectangleV(self.position, LASER_SIZE, COLOR_WHITE),
+                LaserKind::Rolling => {
+                    // a bar with a notch rolling along it
+                    ray::DrawRectangleRec(
+                        Rectangle {
+                            x: x + 1.,
+                            y,
+                            width: 3.,
+                            height: rect.height,
+                        },
+                        LASER_COLOR,
+                    );
+                    for i in 0..3 {
+                        let notch_y = y + 2. + (i as f32 * 2. + frame) * 2.;
+                        let side = (i + frame as i32) % 2;
+                        ray::DrawRectangleRec(
+                            Rectangle {
+                                x: x + side as f32 * 4.,
+                                y: notch_y,
+                                width: 1.,
+                                height: 2.,
+                            },
+                            LASER_COLOR,
+                        );
+                    }
+                }
+                LaserKind::Plunger => {
+                    // a bar with a crossbar moving up and down
+                    ray::DrawRectangleRec(
+                        Rectangle {
+                            x: x + 2.,
+                            y,
+                            width: 3.,
+                            height: rect.height,
+                        },
+                        LASER_COLOR,
+                    );
+                    ray::DrawRectangleRec(
+                        Rectangle {
+                            x,
+                            y: y + 2. + frame * 8.,
+                            width: rect.width,
+                            height: 2.,
+                        },
+                        LASER_COLOR,
+                    );
+                }
+                LaserKind::Bomb => {
+                    // a blinking square
+                    let color = match self.get_frame() {
+                        0 => LASER_COLOR,
+                        _ => COLOR_WHITE,
+                    };
+                    ray::DrawRectangleRec(rect, color);
+                }
+                LaserKind::Squiggly => {
+                    // segments alternating left and right
+                    for i in 0..4 {
+                        let side = (i + frame as i32) % 2;
+                        ray::DrawRectangleRec(
+                            Rectangle {
+                                x: x + side as f32 * 3.,
+                                y: y + i as f32 * 3.5,
+                                width: 3.,
+                                height: 3.5,
+                            },
+                            LASER_COLOR,
+                        );
+                    }
+                }
+            }
         }
     }
 
     pub fn get_rect(&self) -> Rectangle {
+        let size = self.kind.get_size();
         Rectangle {
             x: self.position.x,
             y: self.position.y,
-            width: LASER_WIDTH,
-            height: LASER_HEIGHT,
+            width: size.x,
+            height: size.y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fired(kind: LaserKind) -> Laser {
+        Laser::alien(kind, Vector2 { x: 100., y: 100. }, ALIEN_LASER_SPEED as f32)
+    }
+
+    #[test]
+    fn projectiles_fall_at_their_own_speed() {
+        let mut lasers = [
+            fired(LaserKind::Rolling),
+            fired(LaserKind::Plunger),
+            fired(LaserKind::Squiggly),
+        ];
+        for laser in lasers.iter_mut() {
+            laser.update();
+        }
+        let y: Vec<f32> = lasers.iter().map(|laser| laser.get_rect().y).collect();
+        assert!(y[0] > y[1] && y[1] > y[2], "{:?}", y);
+    }
+
+    #[test]
+    fn the_squiggly_shot_zig_zags_around_its_column() {
+        let mut laser = fired(LaserKind::Squiggly);
+        let start_x = laser.get_rect().x;
+        laser.update();
+        assert_ne!(laser.get_rect().x, start_x);
+        for _ in 1..16 {
+            laser.update();
         }
+        assert_eq!(laser.get_rect().x, start_x);
     }
 }
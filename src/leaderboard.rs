@@ -0,0 +1,330 @@
+// The leaderboard: the best runs, with the player initials, the score,
+// the level reached and the date.
+//
+// It is saved in the data directory as a text file: a version header
+// followed by one run per line.
+//
+//     spaceinv-leaderboard 1
+//     AAA 12345 4 2026-10-18
+//
+// The versions before the leaderboard only kept the best score, as a bare
+// number in ./highscore.txt. It is imported once, as a placeholder run,
+// when there is no leaderboard yet.
+
+use crate::log;
+use crate::storage;
+use std::fmt;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const LEADERBOARD_FILE: &str = "leaderboard.txt";
+pub const LEGACY_HIGH_SCORE_FILE: &str = "highscore.txt";
+const HEADER: &str = "spaceinv-leaderboard";
+const VERSION: u32 = 1;
+pub const LEADERBOARD_SIZE: usize = 10;
+pub const INITIALS_LEN: usize = 3;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Entry {
+    pub initials: String,
+    pub score: usize,
+    pub level: usize,
+    pub date: String,
+}
+
+impl Entry {
+    /// Reads an entry as saved in the file
+    pub fn parse(line: &str) -> Option<Entry> {
+        let mut fields = line.split_whitespace();
+        let entry = Entry {
+            initials: fields.next()?.to_string(),
+            score: fields.next()?.parse().ok()?,
+            level: fields.next()?.parse().ok()?,
+            date: fields.next()?.to_string(),
+        };
+        Some(entry)
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.initials, self.score, self.level, self.date
+        )
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Leaderboard {
+    entries: Vec<Entry>,
+}
+
+impl Leaderboard {
+    /// Loads the leaderboard, starting from an empty one when the file is
+    /// missing or can't be read (see storage::load_or_recover)
+    pub fn load_or_recover(path: &Path) -> Leaderboard {
+        storage::load_or_recover(path, Leaderboard::parse, Leaderboard::default)
+    }
+
+    /// Loads the leaderboard like load_or_recover, importing the high score
+    /// of `legacy_path` when there is no leaderboard yet. The leaderboard
+    /// is saved right away, so that the import happens only once.
+    pub fn load_or_migrate(path: &Path, legacy_path: &Path) -> Leaderboard {
+        let first_run = !path.exists();
+        let mut leaderboard = Leaderboard::load_or_recover(path);
+        if !first_run {
+            return leaderboard;
+        }
+        let score = std::fs::read_to_string(legacy_path)
+            .ok()
+            .and_then(|text| text.trim().parse::<usize>().ok())
+            .filter(|score| *score > 0);
+        if let Some(score) = score {
+            // the old file didn't say who made it, when, nor at which level
+            leaderboard.insert(Entry {
+                initials: "???".to_string(),
+                score,
+                level: 0,
+                date: "????-??-??".to_string(),
+            });
+            match leaderboard.save(path) {
+                Ok(()) => log::info(
+                    format!(
+                        "Migrated the high score {} of {} to {}",
+                        score,
+                        legacy_path.display(),
+                        path.display()
+                    )
+                    .as_str(),
+                ),
+                Err(err) => log::warning(
+                    format!("Could not save the migrated high score: {}", err).as_str(),
+                ),
+            }
+        }
+        leaderboard
+    }
+
+    pub fn parse(text: &str) -> Result<Leaderboard, String> {
+        let mut lines = text.lines().enumerate();
+
+        match lines.next().and_then(|(_, line)| line.strip_prefix(HEADER)) {
+            Some(version) => match version.trim().parse::<u32>() {
+                Ok(VERSION) => (),
+                _ => return Err(format!("unsupported version '{}'", version.trim())),
+            },
+            None => return Err("not a leaderboard file".to_string()),
+        }
+
+        let mut leaderboard = Leaderboard::default();
+        for (index, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = Entry::parse(line)
+                .ok_or_else(|| format!("line {}: invalid entry '{}'", index + 1, line))?;
+            leaderboard.insert(entry);
+        }
+        Ok(leaderboard)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut text = format!("{} {}\n", HEADER, VERSION);
+        for entry in self.entries.iter() {
+            text += &format!("{}\n", entry);
+        }
+        storage::write_atomic(path, &text)
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn best_score(&self) -> usize {
+        self.entries.first().map_or(0, |entry| entry.score)
+    }
+
+    /// Tells if a run with this score makes it into the leaderboard
+    pub fn qualifies(&self, score: usize) -> bool {
+        score > 0
+            && (self.entries.len() < LEADERBOARD_SIZE
+                || self.entries.iter().any(|entry| score > entry.score))
+    }
+
+    /// Adds a run and returns its rank (0 is the best), or None when it
+    /// didn't make it into the leaderboard
+    pub fn insert(&mut self, entry: Entry) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+        // runs with the same score keep their order: older ones stay ahead
+        let rank = self
+            .entries
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(rank, entry);
+        self.entries.truncate(LEADERBOARD_SIZE);
+        Some(rank)
+    }
+}
+
+/// Today's date as YYYY-MM-DD (UTC)
+pub fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Converts a number of days since 1970-01-01 into a (year, month, day)
+// date of the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::test_dir;
+    use crate::storage::LoadError;
+
+    fn load(path: &Path) -> Result<Leaderboard, LoadError> {
+        storage::load(path, Leaderboard::parse, Leaderboard::default)
+    }
+
+    fn entry(initials: &str, score: usize) -> Entry {
+        Entry {
+            initials: initials.to_string(),
+            score,
+            level: 1,
+            date: "2026-10-18".to_string(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_best_runs_in_order() {
+        let mut leaderboard = Leaderboard::default();
+        for score in 1..=LEADERBOARD_SIZE + 2 {
+            leaderboard.insert(entry("AAA", score * 100));
+        }
+        assert_eq!(leaderboard.entries().len(), LEADERBOARD_SIZE);
+        assert_eq!(leaderboard.best_score(), (LEADERBOARD_SIZE + 2) * 100);
+        assert!(!leaderboard.qualifies(300));
+        assert!(leaderboard.qualifies(350));
+
+        assert_eq!(leaderboard.insert(entry("BBB", 650)), Some(6));
+        assert_eq!(leaderboard.insert(entry("CCC", 100)), None);
+        assert_eq!(leaderboard.entries()[6].initials, "BBB");
+    }
+
+    #[test]
+    fn ties_rank_behind_older_runs() {
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.insert(entry("OLD", 500));
+        assert_eq!(leaderboard.insert(entry("NEW", 500)), Some(1));
+    }
+
+    #[test]
+    fn parses_saved_lines() {
+        assert_eq!(
+            Entry::parse("ABC 1200 3 2026-10-18"),
+            Some(Entry {
+                initials: "ABC".to_string(),
+                score: 1200,
+                level: 3,
+                date: "2026-10-18".to_string(),
+            })
+        );
+        assert_eq!(Entry::parse("ABC lots 3 2026-10-18"), None);
+    }
+
+    #[test]
+    fn saves_and_loads_back() {
+        let path = test_dir("leaderboard-save").join(LEADERBOARD_FILE);
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.insert(entry("AAA", 500));
+        leaderboard.insert(entry("BBB", 900));
+        leaderboard.save(&path).unwrap();
+
+        let loaded = load(&path).unwrap();
+        assert_eq!(loaded.entries(), leaderboard.entries());
+    }
+
+    #[test]
+    fn missing_file_is_an_empty_leaderboard() {
+        let path = test_dir("leaderboard-missing").join(LEADERBOARD_FILE);
+        assert!(load(&path).unwrap().entries().is_empty());
+        assert!(Leaderboard::load_or_recover(&path).entries().is_empty());
+    }
+
+    #[test]
+    fn imports_the_legacy_high_score_once() {
+        let dir = test_dir("leaderboard-legacy");
+        let path = dir.join(LEADERBOARD_FILE);
+        let legacy_path = dir.join(LEGACY_HIGH_SCORE_FILE);
+        std::fs::write(&legacy_path, "17600").unwrap();
+
+        let leaderboard = Leaderboard::load_or_migrate(&path, &legacy_path);
+        assert_eq!(leaderboard.best_score(), 17600);
+        assert_eq!(load(&path).unwrap().best_score(), 17600);
+
+        // later runs read the leaderboard alone
+        std::fs::write(&legacy_path, "99999").unwrap();
+        let leaderboard = Leaderboard::load_or_migrate(&path, &legacy_path);
+        assert_eq!(leaderboard.entries().len(), 1);
+        assert_eq!(leaderboard.best_score(), 17600);
+    }
+
+    #[test]
+    fn rejects_corrupt_files() {
+        let err = Leaderboard::parse("spaceinv-leaderboard 1\nABC 12 1 2026-10-18\n17600\n");
+        assert_eq!(err.unwrap_err(), "line 3: invalid entry '17600'");
+        let err = Leaderboard::parse("spaceinv-leaderboard 9\n");
+        assert_eq!(err.unwrap_err(), "unsupported version '9'");
+        let err = Leaderboard::parse("ABC 1200 3 2026-10-18\n");
+        assert_eq!(err.unwrap_err(), "not a leaderboard file");
+    }
+
+    #[test]
+    fn corrupt_files_are_set_aside() {
+        let path = test_dir("leaderboard-corrupt").join(LEADERBOARD_FILE);
+        std::fs::write(&path, "\u{0}\u{1} garbage\n").unwrap();
+
+        assert!(matches!(load(&path), Err(LoadError::Corrupt(_))));
+        let leaderboard = Leaderboard::load_or_recover(&path);
+        assert!(leaderboard.entries().is_empty());
+        assert!(!path.exists());
+        assert!(path.with_extension("txt.bad").exists());
+    }
+
+    #[test]
+    fn unreadable_files_are_left_alone() {
+        // a directory where the file should be can't be read
+        let path = test_dir("leaderboard-unreadable").join(LEADERBOARD_FILE);
+        std::fs::create_dir(&path).unwrap();
+
+        assert!(matches!(load(&path), Err(LoadError::Io(_))));
+        assert!(Leaderboard::load_or_recover(&path).entries().is_empty());
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn converts_days_to_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20744), (2026, 10, 18));
+    }
+}
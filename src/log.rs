@@ -7,3 +7,11 @@ pub fn info(msg: &str) {
         SetTraceLogLevel(TraceLogLevel::Error as i32);
     }
 }
+
+pub fn warning(msg: &str) {
+    unsafe {
+        SetTraceLogLevel(TraceLogLevel::Warning as i32);
+        TraceLog(TraceLogLevel::Warning as i32, rl_str!(msg));
+        SetTraceLogLevel(TraceLogLevel::Error as i32);
+    }
+}
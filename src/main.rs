@@ -9,19 +9,137 @@
 mod alien;
 mod assets;
 mod block;
+mod clock;
+mod config;
 mod constants;
-//mod context;
+mod context;
+mod explosion;
 mod game;
+mod gamepad;
+mod gameplay;
+mod headless;
+mod highscores;
+mod input;
 mod laser;
+mod leaderboard;
 mod log;
+mod menu;
 mod mysteryship;
 mod obstacle;
+mod options;
+mod popup;
+mod powerup;
+mod replay;
+mod resources;
+mod scene;
 mod spaceship;
+mod storage;
+mod title;
+mod ui;
+mod wave;
+mod world;
 //mod audio;
 
+use config::{Config, CONFIG_FILE};
 use game::*;
+use leaderboard::{Leaderboard, LEADERBOARD_FILE, LEGACY_HIGH_SCORE_FILE};
+use options::{Options, USAGE};
+use replay::{Replay, ReplayRecorder};
+use wave::{Wave, WAVES_DIR};
+
+fn exit_with_error(msg: &str) -> ! {
+    eprintln!("spaceinv: {}", msg);
+    std::process::exit(2);
+}
 
 fn main() {
-    let mut game = Game::new();
+    let options = Options::parse(std::env::args().skip(1)).unwrap_or_else(|msg| {
+        eprintln!("{}", USAGE);
+        exit_with_error(&msg)
+    });
+
+    if options.dump_config {
+        print!("{}", Config::default().to_toml());
+        return;
+    }
+
+    // only a config file given explicitly has to exist
+    let config = match options.config.as_deref() {
+        Some(path) if !std::path::Path::new(path).exists() => {
+            exit_with_error(&format!("{}: no such file", path))
+        }
+        Some(path) => Config::load(path.as_ref()),
+        None => Config::load(CONFIG_FILE.as_ref()),
+    }
+    .unwrap_or_else(|msg| exit_with_error(&msg));
+
+    let waves = Wave::load_dir(WAVES_DIR.as_ref(), &config.mystery_ship)
+        .unwrap_or_else(|msg| exit_with_error(&msg));
+
+    if let Some(games) = options.headless {
+        if options.record.is_some() || options.replay.is_some() {
+            exit_with_error("--headless doesn't record nor play back replays");
+        }
+        let seed = options
+            .seed
+            .or(config.game.seed)
+            .unwrap_or_else(rand::random);
+        headless::run(games, seed, &config, &waves);
+        return;
+    }
+
+    let replay = options
+        .replay
+        .as_deref()
+        .map(Replay::load)
+        .transpose()
+        .unwrap_or_else(|msg| exit_with_error(&msg));
+
+    // a replay only plays back right with the configuration and the waves
+    // it was recorded with
+    let fingerprint = replay::fingerprint(&config, &waves);
+    if let (Some(path), Some(replay)) = (options.replay.as_deref(), &replay) {
+        match replay.get_fingerprint() {
+            Some(recorded) if recorded != fingerprint => exit_with_error(&format!(
+                "{}: recorded with another configuration ({} or {}/), it would go out of sync",
+                path, CONFIG_FILE, WAVES_DIR
+            )),
+            None => log::warning(
+                format!(
+                    "{}: the configuration of the replay is unknown, it may go out of sync",
+                    path
+                )
+                .as_str(),
+            ),
+            Some(_) => (),
+        }
+    }
+
+    let seed = match &replay {
+        Some(replay) => replay.get_seed(),
+        None => options
+            .seed
+            .or(config.game.seed)
+            .unwrap_or_else(rand::random),
+    };
+
+    // a replay runs with the leaderboard it was recorded with, so that the
+    // same runs make it into it
+    let leaderboard = match &replay {
+        Some(replay) => replay.get_leaderboard().clone(),
+        None => Leaderboard::load_or_migrate(
+            &storage::data_file(LEADERBOARD_FILE),
+            LEGACY_HIGH_SCORE_FILE.as_ref(),
+        ),
+    };
+
+    let recorder = options
+        .record
+        .as_deref()
+        .map(|path| ReplayRecorder::create(path, seed, fingerprint, &leaderboard))
+        .transpose()
+        .unwrap_or_else(|err| exit_with_error(&format!("cannot record: {}", err)));
+
+    let mut game = Game::new(seed, &config, waves, leaderboard, replay, recorder);
     game.run();
 }
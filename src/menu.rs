@@ -0,0 +1,85 @@
+// The menus: the main one of the title screen and the pause one
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MenuItem {
+    Start,
+    Options,
+    HighScores,
+    Quit,
+    Resume,
+    RestartLevel,
+    QuitToMenu,
+}
+
+pub const MAIN_MENU: &[MenuItem] = &[
+    MenuItem::Start,
+    MenuItem::Options,
+    MenuItem::HighScores,
+    MenuItem::Quit,
+];
+
+pub const PAUSE_MENU: &[MenuItem] = &[
+    MenuItem::Resume,
+    MenuItem::RestartLevel,
+    MenuItem::Options,
+    MenuItem::QuitToMenu,
+];
+
+impl MenuItem {
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            MenuItem::Start => "START",
+            MenuItem::Options => "OPTIONS",
+            MenuItem::HighScores => "HIGH SCORES",
+            MenuItem::Quit => "QUIT",
+            MenuItem::Resume => "RESUME",
+            MenuItem::RestartLevel => "RESTART LEVEL",
+            MenuItem::QuitToMenu => "QUIT TO MENU",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Menu {
+    items: &'static [MenuItem],
+    selected: usize,
+}
+
+impl Menu {
+    pub fn new(items: &'static [MenuItem]) -> Self {
+        Menu { items, selected: 0 }
+    }
+
+    pub fn get_items(&self) -> &'static [MenuItem] {
+        self.items
+    }
+
+    pub fn get_selected(&self) -> MenuItem {
+        self.items[self.selected]
+    }
+
+    // The selection wraps around at both ends
+    pub fn select_previous(&mut self) {
+        self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.items.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_on_the_first_item_and_wraps_around() {
+        let mut menu = Menu::new(MAIN_MENU);
+        assert_eq!(menu.get_selected(), MenuItem::Start);
+        menu.select_previous();
+        assert_eq!(menu.get_selected(), MenuItem::Quit);
+        menu.select_next();
+        menu.select_next();
+        assert_eq!(menu.get_selected(), MenuItem::Options);
+    }
+}
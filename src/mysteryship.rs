@@ -1,33 +1,98 @@
+use crate::config::MysteryShipConfig;
 use crate::constants::*;
+use crate::resources::{Resources, MYSTERYSHIP_TEXTURE};
 use rand::Rng;
-use raylib_ffi::{
-    rl_str, DrawTextureV, GetScreenWidth, LoadImageFromMemory, LoadTextureFromImage, Rectangle,
-    Texture2D, Vector2,
-};
+use raylib_ffi::{DrawTextureRec, Rectangle, Vector2};
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MysteryKind {
+    Classic,
+    // drops bombs on the spaceship while crossing
+    Bomber,
+    // rare, fast and worth more
+    Gold,
+    // drops a collectible when destroyed
+    Carrier,
+}
+
+// In sprite sheet and spawn weight order
+pub const MYSTERY_KINDS: [MysteryKind; 4] = [
+    MysteryKind::Classic,
+    MysteryKind::Bomber,
+    MysteryKind::Gold,
+    MysteryKind::Carrier,
+];
+
+impl MysteryKind {
+    // Where the kind is in MYSTERY_KINDS, and in the config arrays
+    fn get_index(&self) -> usize {
+        match self {
+            MysteryKind::Classic => 0,
+            MysteryKind::Bomber => 1,
+            MysteryKind::Gold => 2,
+            MysteryKind::Carrier => 3,
+        }
+    }
+
+    /// Where the kind is in the sprite sheet: one row each
+    pub fn get_frame_rect(&self) -> Rectangle {
+        let row = self.get_index();
+        Rectangle {
+            x: 0.,
+            y: row as f32 * MYSTERYSHIP_SIZE.y,
+            width: MYSTERYSHIP_SIZE.x,
+            height: MYSTERYSHIP_SIZE.y,
+        }
+    }
+
+    pub fn get_score_factor(&self) -> usize {
+        match self {
+            MysteryKind::Gold => 3,
+            _ => 1,
+        }
+    }
+
+    // Picks a kind at random, each with a chance proportional to its weight
+    fn pick(rng: &mut impl Rng, weights: &[u32; 4]) -> MysteryKind {
+        let total: u32 = weights.iter().sum();
+        let mut roll = rng.gen_range(0..total);
+        for (kind, weight) in MYSTERY_KINDS.iter().zip(weights) {
+            if roll < *weight {
+                return *kind;
+            }
+            roll -= weight;
+        }
+        unreachable!("the roll is below the total weight")
+    }
+}
+
+#[derive(Clone)]
 pub struct MysteryShip {
-    texture: Texture2D,
+    kind: MysteryKind,
     position: Vector2,
     speed: f32,
     active: bool,
+    // pixels per tick of every kind
+    speeds: [f32; 4],
 }
 
 impl MysteryShip {
-    pub fn new() -> Self {
-        let ship_data = include_bytes!("../assets/images/mystery.png");
-        let texture = unsafe {
-            let ship_image =
-                LoadImageFromMemory(rl_str!(".png"), ship_data.as_ptr(), ship_data.len() as i32);
-            LoadTextureFromImage(ship_image)
-        };
+    pub fn new(config: &MysteryShipConfig) -> Self {
         Self {
-            texture,
+            kind: MysteryKind::Classic,
             position: Vector2 { x: 0., y: 0. },
             speed: 0.,
             active: false,
+            speeds: config
+                .speed_factors
+                .map(|factor| (config.speed * factor) as f32),
         }
     }
 
+    pub fn get_kind(&self) -> MysteryKind {
+        self.kind
+    }
+
     pub fn is_active(&self) -> bool {
         self.active
     }
@@ -39,7 +104,7 @@ impl MysteryShip {
     pub fn update(&mut self) {
         if self.active {
             self.position.x += self.speed;
-            if self.position.x > (WORLD_WIDTH - self.texture.width - OFFSETX / 2) as f32
+            if self.position.x > WORLD_WIDTH as f32 - MYSTERYSHIP_SIZE.x - (OFFSETX / 2) as f32
                 || self.position.x < (OFFSETX / 2) as f32
             {
                 self.active = false;
@@ -47,35 +112,48 @@ impl MysteryShip {
         }
     }
 
-    pub fn draw(&self) {
+    pub fn draw(&self, resources: &Resources) {
         if self.active {
             unsafe {
-                DrawTextureV(self.texture, self.position, COLOR_WHITE);
+                let texture = resources.get_texture(MYSTERYSHIP_TEXTURE);
+                let frame = self.kind.get_frame_rect();
+                DrawTextureRec(texture, frame, self.position, COLOR_WHITE);
             }
         }
     }
 
-    pub fn spawn(&mut self) {
-        let swidth = unsafe { GetScreenWidth() };
-        let side: i32 = rand::thread_rng().gen_range(0..1);
+    /// Sends a ship across, of a kind picked with `weights` (see
+    /// MYSTERY_KINDS)
+    pub fn spawn(&mut self, rng: &mut impl Rng, weights: &[u32; 4]) {
+        let side: i32 = rng.gen_range(0..2);
+        self.kind = MysteryKind::pick(rng, weights);
+        let speed = self.speeds[self.kind.get_index()];
         self.position.y = MYSTERYSHIP_YPOS;
         if side == 0 {
             self.position.x = (OFFSETX / 2) as f32;
-            self.speed = MYSTERYSHIP_SPEED;
+            self.speed = speed;
         } else {
-            self.position.x = (swidth - self.texture.width - OFFSETX / 2) as f32;
-            self.speed = -MYSTERYSHIP_SPEED;
+            self.position.x = WORLD_WIDTH as f32 - MYSTERYSHIP_SIZE.x - (OFFSETX / 2) as f32;
+            self.speed = -speed;
         }
         self.active = true;
     }
 
+    // Where the bombs leave the ship
+    pub fn get_bomb_position(&self) -> Vector2 {
+        Vector2 {
+            x: self.position.x + MYSTERYSHIP_SIZE.x / 2.,
+            y: self.position.y + MYSTERYSHIP_SIZE.y,
+        }
+    }
+
     pub fn get_rect(&self) -> Rectangle {
         let mut width: f32 = 0.;
         let mut height: f32 = 0.;
 
         if self.active {
-            width = self.texture.width as f32;
-            height = self.texture.height as f32;
+            width = MYSTERYSHIP_SIZE.x;
+            height = MYSTERYSHIP_SIZE.y;
         }
 
         Rectangle {
@@ -86,3 +164,36 @@ impl MysteryShip {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn the_mystery_ship_comes_from_both_sides() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut sides = std::collections::HashSet::new();
+        for _ in 0..20 {
+            let mut ship = MysteryShip::new(&MysteryShipConfig::default());
+            ship.spawn(&mut rng, &MYSTERYSHIP_WEIGHTS);
+            sides.insert(ship.get_rect().x as i32);
+        }
+        assert_eq!(sides.len(), 2);
+    }
+
+    #[test]
+    fn every_kind_has_its_own_speed() {
+        let ship = MysteryShip::new(&MysteryShipConfig::default());
+        let mut speeds = ship.speeds.to_vec();
+        speeds.sort_by(f32::total_cmp);
+        speeds.dedup();
+        assert_eq!(speeds.len(), MYSTERY_KINDS.len());
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut gold = ship.clone();
+        gold.spawn(&mut rng, &[0, 0, 1, 0]);
+        assert_eq!(gold.speed.abs(), ship.speeds[2]);
+    }
+}
@@ -14,9 +14,9 @@ impl Obstacle {
             // position: Vector2::new(x, y),
             blocks: Vec::new(),
         };
-        for row in 0..GRID_HEIGHT {
-            for col in 0..GRID_WIDTH {
-                if OBSTACLE_GRID[row][col] == 1 {
+        for (row, cells) in OBSTACLE_GRID.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                if *cell == 1 {
                     let pos_x = x + col * BLOCK_SIDE;
                     let pos_y = y + row * BLOCK_SIDE;
                     let block = Block::new(Vector2 {
@@ -0,0 +1,56 @@
+// Command line options
+
+pub const USAGE: &str = "usage: spaceinv [--seed <number>] [--record <replay file>] \
+    [--replay <replay file>] [--config <config file>] [--dump-config] [--headless <games>]";
+
+#[derive(Clone, Default, Debug)]
+pub struct Options {
+    // Seed of the gameplay random generator (random when not given)
+    pub seed: Option<u64>,
+    // Where to record the session input
+    pub record: Option<String>,
+    // Replay to play back (its seed wins over --seed)
+    pub replay: Option<String>,
+    // Gameplay configuration (spaceinv.toml when not given)
+    pub config: Option<String>,
+    // Print the default configuration and exit
+    pub dump_config: bool,
+    // Number of games to play without a window (from --seed on)
+    pub headless: Option<u32>,
+}
+
+impl Options {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().ok_or("--seed needs a value")?;
+                    let seed = value
+                        .parse::<u64>()
+                        .map_err(|_| format!("invalid seed '{}'", value))?;
+                    options.seed = Some(seed);
+                }
+                "--record" => {
+                    options.record = Some(args.next().ok_or("--record needs a file name")?);
+                }
+                "--replay" => {
+                    options.replay = Some(args.next().ok_or("--replay needs a file name")?);
+                }
+                "--config" => {
+                    options.config = Some(args.next().ok_or("--config needs a file name")?);
+                }
+                "--dump-config" => options.dump_config = true,
+                "--headless" => {
+                    let value = args.next().ok_or("--headless needs a number of games")?;
+                    let games = value
+                        .parse::<u32>()
+                        .map_err(|_| format!("invalid number of games '{}'", value))?;
+                    options.headless = Some(games);
+                }
+                _ => return Err(format!("unknown option '{}'", arg)),
+            }
+        }
+        Ok(options)
+    }
+}
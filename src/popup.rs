@@ -0,0 +1,40 @@
+// The points scored by hitting the mystery ship, shown for a moment where
+// it exploded
+
+use crate::constants::*;
+use crate::ui;
+use raylib_ffi::{Font, Vector2};
+
+#[derive(Clone)]
+pub struct ScorePopup {
+    position: Vector2,
+    score: usize,
+    ticks_left: u32,
+}
+
+impl ScorePopup {
+    pub fn new(position: Vector2, score: usize) -> Self {
+        ScorePopup {
+            position,
+            score,
+            ticks_left: SCORE_POPUP_TICKS,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.ticks_left > 0
+    }
+
+    pub fn update(&mut self) {
+        self.ticks_left = self.ticks_left.saturating_sub(1);
+    }
+
+    pub fn draw(&self, font: Font) {
+        ui::draw_text(
+            font,
+            &self.score.to_string(),
+            self.position,
+            MYSTERYSHIP_POPUP_COLOR,
+        );
+    }
+}
@@ -0,0 +1,133 @@
+// Collectibles falling toward the spaceship, picked up by flying into them,
+// and the effects they give
+
+use crate::clock::TICK_RATE;
+use crate::constants::*;
+use crate::ui;
+use rand::Rng;
+use raylib_ffi::{Color, DrawRectangleLinesEx, Font, Rectangle, Vector2};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PowerUpKind {
+    RapidFire,
+    DoubleShot,
+    // the lasers go through the aliens
+    Piercing,
+    Shield,
+    ExtraLife,
+    BunkerRepair,
+}
+
+pub const POWERUP_KINDS: [PowerUpKind; 6] = [
+    PowerUpKind::RapidFire,
+    PowerUpKind::DoubleShot,
+    PowerUpKind::Piercing,
+    PowerUpKind::Shield,
+    PowerUpKind::ExtraLife,
+    PowerUpKind::BunkerRepair,
+];
+
+impl PowerUpKind {
+    pub fn random(rng: &mut impl Rng) -> PowerUpKind {
+        POWERUP_KINDS[rng.gen_range(0..POWERUP_KINDS.len())]
+    }
+
+    // The letter shown on the collectible and in the HUD
+    pub fn get_letter(&self) -> &'static str {
+        match self {
+            PowerUpKind::RapidFire => "R",
+            PowerUpKind::DoubleShot => "D",
+            PowerUpKind::Piercing => "P",
+            PowerUpKind::Shield => "S",
+            PowerUpKind::ExtraLife => "+",
+            PowerUpKind::BunkerRepair => "B",
+        }
+    }
+
+    pub fn get_color(&self) -> Color {
+        match self {
+            PowerUpKind::ExtraLife => GREEN_COLOR,
+            PowerUpKind::BunkerRepair => BLOCK_COLOR,
+            _ => POWERUP_COLOR,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PowerUp {
+    kind: PowerUpKind,
+    position: Vector2,
+    active: bool,
+}
+
+impl PowerUp {
+    /// Creates a collectible centered on `center`
+    pub fn new(kind: PowerUpKind, center: Vector2) -> Self {
+        PowerUp {
+            kind,
+            position: Vector2 {
+                x: center.x - POWERUP_SIZE / 2.,
+                y: center.y - POWERUP_SIZE / 2.,
+            },
+            active: true,
+        }
+    }
+
+    pub fn get_kind(&self) -> PowerUpKind {
+        self.kind
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn set_inactive(&mut self) {
+        self.active = false;
+    }
+
+    pub fn update(&mut self) {
+        if self.active {
+            self.position.y += POWERUP_SPEED;
+            if self.position.y > (WORLD_HEIGHT - OFFSETY) as f32 {
+                self.active = false;
+            }
+        }
+    }
+
+    pub fn draw(&self, font: Font) {
+        let rect = self.get_rect();
+        let color = self.kind.get_color();
+        unsafe { DrawRectangleLinesEx(rect, 2., color) };
+        ui::draw_text_centered_in(font, rect, rect.y - 4., self.kind.get_letter(), color);
+    }
+
+    pub fn get_rect(&self) -> Rectangle {
+        Rectangle {
+            x: self.position.x,
+            y: self.position.y,
+            width: POWERUP_SIZE,
+            height: POWERUP_SIZE,
+        }
+    }
+}
+
+/// A timed power-up the spaceship is enjoying
+#[derive(Clone, Debug)]
+pub struct Effect {
+    pub kind: PowerUpKind,
+    pub ticks_left: u32,
+}
+
+impl Effect {
+    pub fn new(kind: PowerUpKind, seconds: f64) -> Self {
+        Effect {
+            kind,
+            ticks_left: (seconds * TICK_RATE as f64) as u32,
+        }
+    }
+
+    /// Whole seconds left, for the HUD countdown
+    pub fn get_seconds_left(&self) -> u32 {
+        self.ticks_left.div_ceil(TICK_RATE)
+    }
+}
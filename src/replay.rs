@@ -0,0 +1,373 @@
+// Input recording and deterministic replay.
+//
+// A replay is the session seed and the leaderboard the session started
+// with, followed by the player input of every tick. Since the game only
+// depends on those and on the gameplay configuration, feeding them back
+// with the same configuration reproduces the recorded session exactly (the
+// leaderboard decides whether a run asks for initials). The replay keeps a
+// fingerprint of the configuration and of the waves to check it.
+//
+// File format (text, one entry per line):
+//
+//     spaceinv-replay 3
+//     seed 1234
+//     config 9f2c41d07ab3e865
+//     leaderboard AAA 12345 4 2026-10-18
+//     <ticks> <flags>
+//
+// with a leaderboard line per entry, best first. Version 1 files have no
+// config nor leaderboard lines: they play back with an empty leaderboard,
+// whatever the configuration.
+//
+// where <flags> lists the active inputs of a run of identical ticks
+// (L=left, R=right, F=fire, P=pause, E=enter, Q=escape, G/U=debug
+// game over/level up, ^ v < > = menu navigation, W=window lost the focus)
+// followed by K<code> when a key was pressed (the raw key code, which the
+// controls screen binds), or is "-" when no input is active. Version 2
+// files have no key codes.
+
+use crate::config::Config;
+use crate::game::PlayerInput;
+use crate::leaderboard::{Entry, Leaderboard};
+use crate::wave::Wave;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::os::raw::c_int;
+
+const HEADER: &str = "spaceinv-replay";
+const VERSION: u32 = 3;
+
+const FLAGS: [char; 13] = [
+    'L', 'R', 'F', 'P', 'E', 'Q', 'G', 'U', '^', 'v', '<', '>', 'W',
+];
+
+fn input_flags(input: &PlayerInput) -> [bool; 13] {
+    [
+        input.tick.left,
+        input.tick.right,
+        input.tick.fire,
+        input.pause,
+        input.confirm,
+        input.back,
+        input.debug_game_over,
+        input.debug_level_up,
+        input.nav_up,
+        input.nav_down,
+        input.nav_left,
+        input.nav_right,
+        input.focus_lost,
+    ]
+}
+
+fn encode(input: &PlayerInput) -> String {
+    let flags: String = FLAGS
+        .iter()
+        .zip(input_flags(input))
+        .filter(|(_, on)| *on)
+        .map(|(flag, _)| flag)
+        .collect();
+    let key = input.key.map(|key| format!("K{}", key)).unwrap_or_default();
+    if flags.is_empty() && key.is_empty() {
+        "-".to_string()
+    } else {
+        flags + &key
+    }
+}
+
+fn decode(flags: &str) -> Result<PlayerInput, String> {
+    let mut input = PlayerInput::default();
+    if flags == "-" {
+        return Ok(input);
+    }
+    let (flags, key) = match flags.split_once('K') {
+        Some((flags, key)) => (flags, Some(key)),
+        None => (flags, None),
+    };
+    if let Some(key) = key {
+        let key = key
+            .parse::<c_int>()
+            .map_err(|_| format!("invalid key code '{}'", key))?;
+        input.key = Some(key);
+    }
+    for flag in flags.chars() {
+        match flag {
+            'L' => input.tick.left = true,
+            'R' => input.tick.right = true,
+            'F' => input.tick.fire = true,
+            'P' => input.pause = true,
+            'E' => input.confirm = true,
+            'Q' => input.back = true,
+            'G' => input.debug_game_over = true,
+            'U' => input.debug_level_up = true,
+            '^' => input.nav_up = true,
+            'v' => input.nav_down = true,
+            '<' => input.nav_left = true,
+            '>' => input.nav_right = true,
+            'W' => input.focus_lost = true,
+            _ => return Err(format!("unknown input flag '{}'", flag)),
+        }
+    }
+    Ok(input)
+}
+
+/// A fingerprint of what the simulation depends on besides the seed and
+/// the input: the gameplay configuration and the waves
+pub fn fingerprint(config: &Config, waves: &[Wave]) -> u64 {
+    // the seed of the configuration doesn't matter, the replay has its own
+    let mut config = config.clone();
+    config.game.seed = None;
+    let text = config.to_toml() + &format!("{:?}", waves);
+    // 64-bit FNV-1a: unlike the std hasher it is the same in every build
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// A recorded session being played back
+pub struct Replay {
+    seed: u64,
+    fingerprint: Option<u64>,
+    leaderboard: Leaderboard,
+    inputs: VecDeque<(u32, PlayerInput)>,
+}
+
+impl Replay {
+    pub fn load(path: &str) -> Result<Replay, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Replay::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn parse(text: &str) -> Result<Replay, String> {
+        let mut lines = text.lines().enumerate();
+
+        let version = match lines.next() {
+            Some((_, line)) if line.starts_with(HEADER) => line[HEADER.len()..].trim(),
+            _ => return Err("not a replay file".to_string()),
+        };
+        match version.parse::<u32>() {
+            Ok(1..=VERSION) => (),
+            _ => return Err(format!("unsupported version '{}'", version)),
+        }
+
+        let seed = match lines.next() {
+            Some((_, line)) if line.starts_with("seed ") => line[5..]
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| "line 2: invalid seed".to_string())?,
+            _ => return Err("line 2: missing seed".to_string()),
+        };
+
+        let mut fingerprint = None;
+        let mut leaderboard = Leaderboard::default();
+        let mut inputs = VecDeque::new();
+        for (index, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let error = |msg: &str| format!("line {}: {}", index + 1, msg);
+            if let Some(value) = line.strip_prefix("config ") {
+                let value = u64::from_str_radix(value.trim(), 16)
+                    .map_err(|_| error("invalid config fingerprint"))?;
+                fingerprint = Some(value);
+                continue;
+            }
+            if let Some(entry) = line.strip_prefix("leaderboard ") {
+                let entry =
+                    Entry::parse(entry).ok_or_else(|| error("invalid leaderboard entry"))?;
+                leaderboard.insert(entry);
+                continue;
+            }
+            let (count, flags) = line
+                .trim()
+                .split_once(' ')
+                .ok_or_else(|| error("expected '<ticks> <flags>'"))?;
+            let count = count
+                .parse::<u32>()
+                .map_err(|_| error("invalid tick count"))?;
+            let input = decode(flags).map_err(|e| error(&e))?;
+            inputs.push_back((count, input));
+        }
+
+        Ok(Replay {
+            seed,
+            fingerprint,
+            leaderboard,
+            inputs,
+        })
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The fingerprint of the configuration the session was recorded with
+    /// (None for the files that don't have it)
+    pub fn get_fingerprint(&self) -> Option<u64> {
+        self.fingerprint
+    }
+
+    /// The leaderboard the recorded session started with
+    pub fn get_leaderboard(&self) -> &Leaderboard {
+        &self.leaderboard
+    }
+
+    /// Returns the input of the next tick, or None when the replay is over
+    pub fn next_input(&mut self) -> Option<PlayerInput> {
+        while let Some((count, input)) = self.inputs.front_mut() {
+            if *count > 0 {
+                *count -= 1;
+                return Some(*input);
+            }
+            self.inputs.pop_front();
+        }
+        None
+    }
+}
+
+/// Writes the input of every tick to a replay file
+pub struct ReplayRecorder {
+    writer: BufWriter<File>,
+    last: Option<PlayerInput>,
+    count: u32,
+}
+
+impl ReplayRecorder {
+    pub fn create(
+        path: &str,
+        seed: u64,
+        fingerprint: u64,
+        leaderboard: &Leaderboard,
+    ) -> std::io::Result<ReplayRecorder> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{} {}", HEADER, VERSION)?;
+        writeln!(writer, "seed {}", seed)?;
+        writeln!(writer, "config {:016x}", fingerprint)?;
+        for entry in leaderboard.entries() {
+            writeln!(writer, "leaderboard {}", entry)?;
+        }
+        Ok(ReplayRecorder {
+            writer,
+            last: None,
+            count: 0,
+        })
+    }
+
+    pub fn record(&mut self, input: &PlayerInput) -> std::io::Result<()> {
+        if self.last == Some(*input) {
+            self.count += 1;
+            return Ok(());
+        }
+        self.write_run()?;
+        self.last = Some(*input);
+        self.count = 1;
+        Ok(())
+    }
+
+    fn write_run(&mut self) -> std::io::Result<()> {
+        if let Some(last) = self.last {
+            writeln!(self.writer, "{} {}", self.count, encode(&last))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ReplayRecorder {
+    fn drop(&mut self) {
+        // the buffered writer flushes itself when dropped
+        let _ = self.write_run();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::test_dir;
+    use raylib_ffi::enums::KeyboardKey;
+
+    #[test]
+    fn parses_runs_of_ticks() {
+        let mut replay = Replay::parse("spaceinv-replay 1\nseed 42\n2 LF\n1 -\n1 P\n").unwrap();
+        assert_eq!(replay.get_seed(), 42);
+
+        let moving = replay.next_input().unwrap();
+        assert!(moving.tick.left && moving.tick.fire && !moving.tick.right);
+        assert_eq!(replay.next_input(), Some(moving));
+        assert_eq!(replay.next_input(), Some(PlayerInput::default()));
+        assert!(replay.next_input().unwrap().pause);
+        assert_eq!(replay.next_input(), None);
+    }
+
+    #[test]
+    fn encodes_what_it_decodes() {
+        for flags in ["-", "L", "RF", "LRFPEQGU^v<>W", "K65", "EK257"] {
+            assert_eq!(encode(&decode(flags).unwrap()), flags);
+        }
+    }
+
+    #[test]
+    fn keeps_the_pressed_key() {
+        let input = PlayerInput {
+            confirm: true,
+            key: Some(KeyboardKey::F5 as c_int),
+            ..PlayerInput::default()
+        };
+        assert_eq!(decode(&encode(&input)).unwrap(), input);
+        let err = decode("K").unwrap_err();
+        assert_eq!(err, "invalid key code ''");
+    }
+
+    #[test]
+    fn reports_the_offending_line() {
+        let err = Replay::parse("spaceinv-replay 1\nseed 1\n3 L\n1 X\n").err();
+        assert_eq!(err.unwrap(), "line 4: unknown input flag 'X'");
+        let err = Replay::parse("spaceinv-replay 1\nseed 1\nten L\n").err();
+        assert_eq!(err.unwrap(), "line 3: invalid tick count");
+        assert!(Replay::parse("hello\n").is_err());
+        let err = Replay::parse("spaceinv-replay 4\nseed 1\n").err();
+        assert_eq!(err.unwrap(), "unsupported version '4'");
+    }
+
+    #[test]
+    fn brings_back_the_recorded_leaderboard() {
+        let path = test_dir("replay-leaderboard").join("session.replay");
+        let mut leaderboard = Leaderboard::default();
+        for (initials, score) in [("AAA", 900), ("BBB", 400)] {
+            leaderboard.insert(Entry {
+                initials: initials.to_string(),
+                score,
+                level: 2,
+                date: "2026-10-18".to_string(),
+            });
+        }
+        let mut recorder =
+            ReplayRecorder::create(path.to_str().unwrap(), 7, 0xabc, &leaderboard).unwrap();
+        recorder.record(&PlayerInput::default()).unwrap();
+        drop(recorder);
+
+        let replay = Replay::load(path.to_str().unwrap()).unwrap();
+        assert_eq!(replay.get_fingerprint(), Some(0xabc));
+        assert_eq!(replay.get_leaderboard().entries(), leaderboard.entries());
+        assert!(Replay::parse("spaceinv-replay 1\nseed 1\n1 -\n")
+            .unwrap()
+            .get_leaderboard()
+            .entries()
+            .is_empty());
+    }
+
+    #[test]
+    fn the_fingerprint_follows_the_configuration_but_not_the_seed() {
+        let config = Config::default();
+        let waves = vec![Wave::classic()];
+        let reference = fingerprint(&config, &waves);
+
+        let mut seeded = config.clone();
+        seeded.game.seed = Some(42);
+        assert_eq!(fingerprint(&seeded, &waves), reference);
+
+        let mut faster = config.clone();
+        faster.player.speed += 1.;
+        assert_ne!(fingerprint(&faster, &waves), reference);
+        assert_ne!(fingerprint(&config, &[]), reference);
+    }
+}
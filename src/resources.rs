@@ -0,0 +1,124 @@
+// The texture registry.
+//
+// Every sprite of the game is uploaded to the GPU once, when the registry
+// is created, and unloaded when it is dropped. The entities draw with the
+// TextureHandle of their sprite (a plain index, known in advance for every
+// sprite), so they can be created and copied freely without touching any
+// texture, and the headless simulation never needs the registry at all.
+
+use raylib_ffi::{rl_str, LoadImageFromMemory, LoadTextureFromImage, Texture2D, UnloadImage};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TextureHandle(usize);
+
+pub const SPACESHIP_TEXTURE: TextureHandle = TextureHandle(0);
+// All the mystery ship kinds (see MysteryKind::get_frame_rect)
+pub const MYSTERYSHIP_TEXTURE: TextureHandle = TextureHandle(1);
+// The animation frames of all the aliens (see Alien::get_frame_rect)
+pub const ALIENS_TEXTURE: TextureHandle = TextureHandle(2);
+pub const EXPLOSION_TEXTURE: TextureHandle = TextureHandle(3);
+
+// The embedded images, in handle order
+const SPRITES: [&[u8]; 4] = [
+    include_bytes!("../assets/images/spaceship.png"),
+    include_bytes!("../assets/images/mysteryships.png"),
+    include_bytes!("../assets/images/aliens.png"),
+    include_bytes!("../assets/images/explosion.png"),
+];
+
+fn load_png_texture(png: &[u8]) -> Texture2D {
+    unsafe {
+        let image = LoadImageFromMemory(rl_str!(".png"), png.as_ptr(), png.len() as i32);
+        let texture = LoadTextureFromImage(image);
+        UnloadImage(image);
+        texture
+    }
+}
+
+fn unload_texture(texture: Texture2D) {
+    unsafe { raylib_ffi::UnloadTexture(texture) };
+}
+
+pub struct Resources {
+    textures: Vec<Texture2D>,
+    unload: fn(Texture2D),
+}
+
+impl Resources {
+    /// Loads all the sprites (the window must already be open)
+    pub fn new() -> Self {
+        Resources::with_loader(load_png_texture, unload_texture)
+    }
+
+    fn with_loader(load: fn(&[u8]) -> Texture2D, unload: fn(Texture2D)) -> Self {
+        Resources {
+            textures: SPRITES.iter().map(|png| load(png)).collect(),
+            unload,
+        }
+    }
+
+    pub fn get_texture(&self, handle: TextureHandle) -> Texture2D {
+        self.textures[handle.0]
+    }
+
+    pub fn get_loaded_count(&self) -> usize {
+        self.textures.len()
+    }
+
+    /// Unloads all the textures (done anyway when the registry is dropped,
+    /// but the window may be closed by then)
+    pub fn unload_all(&mut self) {
+        for texture in self.textures.drain(..) {
+            (self.unload)(texture);
+        }
+    }
+}
+
+impl Drop for Resources {
+    fn drop(&mut self) {
+        self.unload_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        static UPLOADED: Cell<u32> = const { Cell::new(0) };
+        static UNLOADED: Cell<u32> = const { Cell::new(0) };
+    }
+
+    fn fake_load(_png: &[u8]) -> Texture2D {
+        let id = UPLOADED.with(|uploaded| {
+            uploaded.set(uploaded.get() + 1);
+            uploaded.get()
+        });
+        Texture2D {
+            id,
+            width: 0,
+            height: 0,
+            mipmaps: 1,
+            format: 0,
+        }
+    }
+
+    fn fake_unload(_texture: Texture2D) {
+        UNLOADED.with(|unloaded| unloaded.set(unloaded.get() + 1));
+    }
+
+    #[test]
+    fn loads_every_sprite_once_and_unloads_them() {
+        let mut resources = Resources::with_loader(fake_load, fake_unload);
+        assert_eq!(UPLOADED.with(Cell::get), SPRITES.len() as u32);
+        assert_eq!(resources.get_loaded_count(), SPRITES.len());
+
+        // unloading early (as the game does before closing the window)
+        // leaves nothing for the drop
+        resources.unload_all();
+        assert_eq!(resources.get_loaded_count(), 0);
+        drop(resources);
+        assert_eq!(UNLOADED.with(Cell::get), SPRITES.len() as u32);
+    }
+}
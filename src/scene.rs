@@ -0,0 +1,198 @@
+// Scenes and the scene stack.
+//
+// Every screen of the game (title, gameplay, dialogs, ...) is a scene that
+// handles its own input, update and drawing. Scenes live on a stack: only
+// the top one gets the input and is updated, and it tells the stack what to
+// do next by returning a Transition. Overlays (pause, dialogs) are drawn on
+// top of the scenes below them, which stay frozen.
+//
+// Scenes work on a shared context `C` (the game uses context::Context).
+
+use crate::game::PlayerInput;
+
+pub enum Transition<C> {
+    /// Stay on the current scene
+    None,
+    /// Put a scene on top of the current one
+    Push(Box<dyn Scene<C>>),
+    /// Go back to the scene below
+    Pop,
+    /// Replace the current scene
+    Replace(Box<dyn Scene<C>>),
+    /// Replace the whole stack
+    ReplaceAll(Box<dyn Scene<C>>),
+    /// Leave the game
+    Quit,
+}
+
+pub trait Scene<C> {
+    /// Handles the player input of one tick
+    fn handle_input(&mut self, ctx: &mut C, input: &PlayerInput) -> Transition<C>;
+
+    /// Advances the scene by one tick (after handling the input)
+    fn update(&mut self, _ctx: &mut C) -> Transition<C> {
+        Transition::None
+    }
+
+    fn draw(&self, ctx: &C);
+
+    /// Overlays are drawn over the scene below them
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+pub struct SceneStack<C> {
+    scenes: Vec<Box<dyn Scene<C>>>,
+}
+
+impl<C> SceneStack<C> {
+    pub fn new(first: Box<dyn Scene<C>>) -> Self {
+        SceneStack {
+            scenes: vec![first],
+        }
+    }
+
+    /// The stack is empty once the game is over
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    pub fn handle_input(&mut self, ctx: &mut C, input: &PlayerInput) {
+        if let Some(scene) = self.scenes.last_mut() {
+            let transition = scene.handle_input(ctx, input);
+            self.apply(transition);
+        }
+    }
+
+    pub fn update(&mut self, ctx: &mut C) {
+        if let Some(scene) = self.scenes.last_mut() {
+            let transition = scene.update(ctx);
+            self.apply(transition);
+        }
+    }
+
+    /// Draws the top scene, over the scenes below it if it is an overlay
+    pub fn draw(&self, ctx: &C) {
+        let first = self
+            .scenes
+            .iter()
+            .rposition(|scene| !scene.is_overlay())
+            .unwrap_or(0);
+        for scene in self.scenes[first..].iter() {
+            scene.draw(ctx);
+        }
+    }
+
+    pub fn apply(&mut self, transition: Transition<C>) {
+        match transition {
+            Transition::None => (),
+            Transition::Push(scene) => self.scenes.push(scene),
+            Transition::Pop => {
+                self.scenes.pop();
+            }
+            Transition::Replace(scene) => {
+                self.scenes.pop();
+                self.scenes.push(scene);
+            }
+            Transition::ReplaceAll(scene) => {
+                self.scenes.clear();
+                self.scenes.push(scene);
+            }
+            Transition::Quit => self.scenes.clear(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    // The context records what the scenes did
+    type Log = RefCell<Vec<String>>;
+
+    struct TestScene {
+        name: &'static str,
+        overlay: bool,
+    }
+
+    fn scene(name: &'static str, overlay: bool) -> Box<dyn Scene<Log>> {
+        Box::new(TestScene { name, overlay })
+    }
+
+    impl Scene<Log> for TestScene {
+        fn handle_input(&mut self, log: &mut Log, input: &PlayerInput) -> Transition<Log> {
+            log.borrow_mut().push(format!("input {}", self.name));
+            match (input.pause, input.back) {
+                (true, _) => Transition::Push(scene("pause", true)),
+                (_, true) => Transition::Pop,
+                _ => Transition::None,
+            }
+        }
+
+        fn draw(&self, log: &Log) {
+            log.borrow_mut().push(format!("draw {}", self.name));
+        }
+
+        fn is_overlay(&self) -> bool {
+            self.overlay
+        }
+    }
+
+    fn drawn(stack: &SceneStack<Log>) -> Vec<String> {
+        let log = Log::default();
+        stack.draw(&log);
+        log.into_inner()
+    }
+
+    #[test]
+    fn only_the_top_scene_gets_the_input() {
+        let mut log = Log::default();
+        let mut stack = SceneStack::new(scene("game", false));
+        let pause = PlayerInput {
+            pause: true,
+            ..Default::default()
+        };
+        stack.handle_input(&mut log, &pause);
+        stack.handle_input(&mut log, &PlayerInput::default());
+        assert_eq!(log.into_inner(), ["input game", "input pause"]);
+        assert_eq!(drawn(&stack), ["draw game", "draw pause"]);
+    }
+
+    #[test]
+    fn overlays_are_drawn_over_the_scenes_below() {
+        let mut stack = SceneStack::new(scene("title", false));
+        stack.apply(Transition::Push(scene("game", false)));
+        assert_eq!(drawn(&stack), ["draw game"]);
+
+        stack.apply(Transition::Push(scene("pause", true)));
+        stack.apply(Transition::Push(scene("dialog", true)));
+        assert_eq!(drawn(&stack), ["draw game", "draw pause", "draw dialog"]);
+
+        stack.apply(Transition::Replace(scene("options", false)));
+        assert_eq!(drawn(&stack), ["draw options"]);
+    }
+
+    #[test]
+    fn transitions_reshape_the_stack() {
+        let mut stack = SceneStack::new(scene("title", false));
+        stack.apply(Transition::Push(scene("game", false)));
+        stack.apply(Transition::Push(scene("pause", true)));
+        stack.apply(Transition::ReplaceAll(scene("title", false)));
+        assert_eq!(drawn(&stack), ["draw title"]);
+
+        // popping the last scene ends the game, like quitting does
+        let back = PlayerInput {
+            back: true,
+            ..Default::default()
+        };
+        stack.handle_input(&mut Log::default(), &back);
+        assert!(stack.is_empty());
+
+        let mut stack = SceneStack::new(scene("title", false));
+        stack.apply(Transition::Push(scene("game", false)));
+        stack.apply(Transition::Quit);
+        assert!(stack.is_empty());
+    }
+}
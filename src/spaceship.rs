@@ -1,43 +1,46 @@
+use crate::config::PlayerConfig;
 use crate::constants::*;
 use crate::laser::Laser;
-use ray::{Rectangle, Texture2D, Vector2};
+use crate::resources::{Resources, SPACESHIP_TEXTURE};
+use ray::{Rectangle, Vector2};
 use raylib_ffi as ray;
 
+/// What the power-ups change to the gun of the spaceship
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Weapon {
+    pub rapid_fire: bool,
+    pub double_shot: bool,
+    pub piercing: bool,
+}
+
 #[derive(Clone)]
 struct Bounds {
     min: f32,
     max: f32,
 }
 
+#[derive(Clone)]
 pub struct Spaceship {
-    texture: Texture2D,
     position: Vector2,
     bounds: Bounds,
     last_fire_time: f64,
+    speed: f32,
+    laser_speed: f32,
+    laser_cooldown: f64,
 }
 
 impl Spaceship {
-    pub fn new() -> Self {
-        let ship_data = include_bytes!("../assets/images/spaceship.png");
-        let texture = unsafe {
-            let ship_image = ray::LoadImageFromMemory(
-                ray::rl_str!(".png"),
-                ship_data.as_ptr(),
-                ship_data.len() as i32,
-            );
-            ray::LoadTextureFromImage(ship_image)
-        };
-        let width = texture.width;
-        let height = texture.height;
+    pub fn new(config: &PlayerConfig) -> Self {
+        let width = SPACESHIP_SIZE.x;
+        let height = SPACESHIP_SIZE.y;
 
-        let ship_x = (WORLD_WIDTH - width) as f32 / 2.;
-        let ship_y = (WORLD_HEIGHT - height - SPACESHIP_YOFFSET) as f32;
+        let ship_x = (WORLD_WIDTH as f32 - width) / 2.;
+        let ship_y = WORLD_HEIGHT as f32 - height - SPACESHIP_YOFFSET as f32;
 
         let ship_min = SPACESHIP_XOFFSET as f32;
-        let ship_max = (WORLD_WIDTH - width - SPACESHIP_XOFFSET) as f32;
+        let ship_max = (WORLD_WIDTH - SPACESHIP_XOFFSET) as f32 - width;
 
         Self {
-            texture,
             position: Vector2 {
                 x: ship_x,
                 y: ship_y,
@@ -47,13 +50,16 @@ impl Spaceship {
                 max: ship_max,
             },
             last_fire_time: 0.,
+            speed: config.speed as f32,
+            laser_speed: config.laser_speed as f32,
+            laser_cooldown: config.laser_cooldown,
         }
     }
 
     pub fn reset(&mut self) {
         // put back the spacehip at the center
-        let ship_x = (WORLD_WIDTH - self.texture.width) / 2;
-        let ship_y = WORLD_HEIGHT - self.texture.height - SPACESHIP_YOFFSET;
+        let ship_x = (WORLD_WIDTH - SPACESHIP_SIZE.x as i32) / 2;
+        let ship_y = WORLD_HEIGHT - SPACESHIP_SIZE.y as i32 - SPACESHIP_YOFFSET;
         self.position.x = ship_x as f32;
         self.position.y = ship_y as f32;
     }
@@ -61,57 +67,65 @@ impl Spaceship {
     // currently unused
     pub fn update(&mut self) {}
 
-    pub fn draw(&self) {
-        unsafe {
-            ray::DrawTextureV(self.texture, self.position, COLOR_WHITE);
-        }
-    }
-
-    pub fn draw_at(&self, x: f32, y: f32) {
-        let pos = Vector2 { x, y };
+    pub fn draw(&self, resources: &Resources) {
         unsafe {
-            ray::DrawTextureV(self.texture, pos, COLOR_WHITE);
+            let texture = resources.get_texture(SPACESHIP_TEXTURE);
+            ray::DrawTextureV(texture, self.position, COLOR_WHITE);
         }
     }
 
     pub fn move_left(&mut self) {
-        self.position.x -= SPACESHIP_SPEED;
+        self.position.x -= self.speed;
         if self.position.x < self.bounds.min {
             self.position.x = self.bounds.min;
         }
     }
 
     pub fn move_right(&mut self) {
-        self.position.x += SPACESHIP_SPEED;
+        self.position.x += self.speed;
         if self.position.x > self.bounds.max {
             self.position.x = self.bounds.max;
         }
     }
 
-    pub fn fire_laser(&mut self) -> Option<Laser> {
-        unsafe {
-            if ray::GetTime() - self.last_fire_time >= LASER_TIME {
+    /// Fires when the gun is ready, one laser or two side by side
+    pub fn fire_lasers(&mut self, now: f64, weapon: Weapon) -> Vec<Laser> {
+        let cooldown = match weapon.rapid_fire {
+            true => self.laser_cooldown / RAPID_FIRE_FACTOR,
+            false => self.laser_cooldown,
+        };
+        if now - self.last_fire_time < cooldown {
+            return Vec::new();
+        }
+        self.last_fire_time = now;
+
+        let center_x = self.position.x + (SPACESHIP_SIZE.x - LASER_WIDTH) / 2.;
+        let offsets: &[f32] = match weapon.double_shot {
+            true => &[-DOUBLE_SHOT_SPREAD / 2., DOUBLE_SHOT_SPREAD / 2.],
+            false => &[0.],
+        };
+        offsets
+            .iter()
+            .map(|dx| {
                 let laser_pos = Vector2 {
-                    x: self.position.x + (self.texture.width as f32 - LASER_WIDTH) / 2.,
+                    x: center_x + dx,
                     y: self.position.y,
                 };
-                self.last_fire_time = ray::GetTime();
-
-                Some(Laser::new(laser_pos, LASER_SPEED))
-            } else {
-                None
-            }
-        }
+                // the player lasers go up
+                match weapon.piercing {
+                    true => Laser::piercing(laser_pos, -self.laser_speed),
+                    false => Laser::new(laser_pos, -self.laser_speed),
+                }
+            })
+            .collect()
     }
 
     pub fn get_rect(&self) -> Rectangle {
-        let width = self.texture.width as f32;
-        let height = self.texture.height as f32;
         Rectangle {
             x: self.position.x,
             y: self.position.y,
-            width: width,
-            height: height,
+            width: SPACESHIP_SIZE.x,
+            height: SPACESHIP_SIZE.y,
         }
     }
 }
@@ -0,0 +1,173 @@
+// Where and how the game data is saved.
+//
+// Files live in the per-user data directory ($XDG_DATA_HOME/spaceinv, or
+// ~/.local/share/spaceinv when it isn't set) and are always written
+// atomically: the new contents go to a temporary file first, which then
+// replaces the old one, so a crash never leaves a half written file.
+
+use crate::log;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const APP_DIR: &str = "spaceinv";
+
+fn non_empty(value: Option<OsString>) -> Option<OsString> {
+    value.filter(|value| !value.is_empty())
+}
+
+// The data directory given the values of $XDG_DATA_HOME and $HOME
+fn data_dir_from(xdg_data_home: Option<OsString>, home: Option<OsString>) -> Option<PathBuf> {
+    if let Some(dir) = non_empty(xdg_data_home) {
+        return Some(PathBuf::from(dir).join(APP_DIR));
+    }
+    non_empty(home).map(|home| {
+        PathBuf::from(home)
+            .join(".local")
+            .join("share")
+            .join(APP_DIR)
+    })
+}
+
+/// The per-user data directory, if there is one
+pub fn data_dir() -> Option<PathBuf> {
+    data_dir_from(
+        std::env::var_os("XDG_DATA_HOME"),
+        std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")),
+    )
+}
+
+/// Path of a data file (in the current directory when there is no
+/// per-user data directory)
+pub fn data_file(name: &str) -> PathBuf {
+    match data_dir() {
+        Some(dir) => dir.join(name),
+        None => PathBuf::from(name),
+    }
+}
+
+/// Replaces the contents of `path`, creating its directory if needed
+pub fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut tmp_name = path.as_os_str().to_owned();
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+
+    let mut tmp_file = File::create(&tmp_path)?;
+    tmp_file.write_all(contents.as_bytes())?;
+    tmp_file.sync_all()?;
+    drop(tmp_file);
+    fs::rename(&tmp_path, path)
+}
+
+/// Moves an unreadable file out of the way (to `<name>.bad`), so that it
+/// isn't overwritten and can still be looked at. Returns the new path.
+pub fn set_aside(path: &Path) -> std::io::Result<PathBuf> {
+    let mut bad_name = path.as_os_str().to_owned();
+    bad_name.push(".bad");
+    let bad_path = PathBuf::from(bad_name);
+    fs::rename(path, &bad_path)?;
+    Ok(bad_path)
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    Corrupt(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "{}", err),
+            LoadError::Corrupt(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+/// Reads a data file with `parse`. A missing file gives `default()`.
+pub fn load<T>(
+    path: &Path,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+    default: impl FnOnce() -> T,
+) -> Result<T, LoadError> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse(&text).map_err(LoadError::Corrupt),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(default()),
+        Err(err) => Err(LoadError::Io(err)),
+    }
+}
+
+/// Reads a data file like load, falling back to `default()` when it can't
+/// be read. A corrupt file is set aside rather than overwritten.
+pub fn load_or_recover<T>(
+    path: &Path,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+    default: impl Fn() -> T,
+) -> T {
+    match load(path, parse, &default) {
+        Ok(value) => value,
+        Err(LoadError::Io(err)) => {
+            log::warning(format!("Cannot read {}: {}", path.display(), err).as_str());
+            default()
+        }
+        Err(LoadError::Corrupt(msg)) => {
+            let msg = format!("Corrupt file {}: {}", path.display(), msg);
+            match set_aside(path) {
+                Ok(bad_path) => {
+                    log::warning(format!("{}, moved to {}", msg, bad_path.display()).as_str())
+                }
+                Err(err) => log::warning(format!("{} ({})", msg, err).as_str()),
+            }
+            default()
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// A fresh, empty directory for a test
+    pub fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spaceinv-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn prefers_xdg_data_home() {
+        let dir = data_dir_from(Some("/data".into()), Some("/home/me".into()));
+        assert_eq!(dir, Some(PathBuf::from("/data/spaceinv")));
+    }
+
+    #[test]
+    fn falls_back_to_local_share() {
+        let dir = data_dir_from(Some("".into()), Some("/home/me".into()));
+        assert_eq!(dir, Some(PathBuf::from("/home/me/.local/share/spaceinv")));
+        assert_eq!(data_dir_from(None, None), None);
+    }
+
+    #[test]
+    fn atomic_writes_create_the_directory_and_replace_the_file() {
+        let path = test_dir("write").join("sub").join("data.txt");
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert!(!path.with_extension("txt.tmp").exists());
+    }
+
+    #[test]
+    fn set_aside_keeps_the_old_contents() {
+        let path = test_dir("aside").join("data.txt");
+        fs::write(&path, "garbage").unwrap();
+        let bad_path = set_aside(&path).unwrap();
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(bad_path).unwrap(), "garbage");
+    }
+}
@@ -0,0 +1,216 @@
+// The title screen with the main menu, and the options screen with the
+// controls
+
+use crate::alien::Alien;
+use crate::constants::*;
+use crate::context::Context;
+use crate::game::PlayerInput;
+use crate::gameplay::GameplayScene;
+use crate::highscores::LeaderboardScene;
+use crate::input::{key_name, Action, REBINDABLE_ACTIONS};
+use crate::menu::{Menu, MenuItem, MAIN_MENU};
+use crate::resources::{ALIENS_TEXTURE, MYSTERYSHIP_TEXTURE};
+use crate::scene::{Scene, Transition};
+use crate::ui;
+use raylib_ffi::{
+    rl_str, DrawRectangleLinesEx, DrawTextEx, DrawTextureRec, MeasureTextEx, Rectangle, Vector2,
+};
+
+pub struct TitleScene {
+    menu: Menu,
+}
+
+impl Default for TitleScene {
+    fn default() -> Self {
+        TitleScene {
+            menu: Menu::new(MAIN_MENU),
+        }
+    }
+}
+
+impl Scene<Context> for TitleScene {
+    fn handle_input(&mut self, ctx: &mut Context, input: &PlayerInput) -> Transition<Context> {
+        if input.nav_up {
+            self.menu.select_previous();
+        }
+        if input.nav_down {
+            self.menu.select_next();
+        }
+        if input.back {
+            return Transition::Quit;
+        }
+        if !input.confirm {
+            return Transition::None;
+        }
+        match self.menu.get_selected() {
+            MenuItem::Start => {
+                ctx.init_game();
+                Transition::Replace(Box::new(GameplayScene::default()))
+            }
+            MenuItem::Options => Transition::Push(Box::new(OptionsScene::default())),
+            MenuItem::HighScores => Transition::Push(Box::new(LeaderboardScene::new())),
+            MenuItem::Quit => Transition::Quit,
+            _ => Transition::None,
+        }
+    }
+
+    fn draw(&self, ctx: &Context) {
+        const SCORE_ROW_HEIGHT: f32 = 50.;
+        let screen = FRAME_RECT;
+        let font = ctx.assets.get_font();
+
+        // the logo
+        unsafe {
+            for (row, word) in ["SPACE", "INVADERS"].iter().enumerate() {
+                let size = MeasureTextEx(font, rl_str!(*word), TITLE_FONT_SIZE, FONT_SPACING);
+                let pos = Vector2 {
+                    x: screen.x + (screen.width - size.x) / 2.,
+                    y: TITLE_LOGO_Y + row as f32 * size.y,
+                };
+                DrawTextEx(
+                    font,
+                    rl_str!(*word),
+                    pos,
+                    TITLE_FONT_SIZE,
+                    FONT_SPACING,
+                    FRAME_COLOR,
+                );
+            }
+        }
+
+        let high_score = format!("HIGH SCORE {:0>5}", ctx.high_score());
+        ui::draw_text_centered_in(font, screen, TITLE_LOGO_Y - 60., &high_score, FRAME_COLOR);
+
+        ui::draw_menu(font, screen, TITLE_MENU_Y, &self.menu);
+
+        // what every enemy is worth, like on the arcade attract screen
+        let mut y = TITLE_SCORE_TABLE_Y;
+        ui::draw_text_centered_in(font, screen, y, "*SCORE ADVANCE TABLE*", FRAME_COLOR);
+        y += SCORE_ROW_HEIGHT;
+
+        let config = ctx.world.get_config();
+        let mut rows = vec![(
+            ctx.resources.get_texture(MYSTERYSHIP_TEXTURE),
+            Rectangle {
+                x: 0.,
+                y: 0.,
+                width: MYSTERYSHIP_SIZE.x,
+                height: MYSTERYSHIP_SIZE.y,
+            },
+            "= ? MYSTERY".to_string(),
+        )];
+        for kind in [ALIEN3, ALIEN2, ALIEN1] {
+            rows.push((
+                ctx.resources.get_texture(ALIENS_TEXTURE),
+                Alien::get_frame_rect(kind, 0),
+                format!("= {} POINTS", config.aliens.scores[kind]),
+            ));
+        }
+        for (texture, source, label) in rows {
+            let sprite_pos = Vector2 {
+                x: TITLE_SCORE_TABLE_X - source.width / 2.,
+                y: y + (FONT_SIZE as f32 - source.height) / 2.,
+            };
+            unsafe { DrawTextureRec(texture, source, sprite_pos, COLOR_WHITE) };
+            let text_pos = Vector2 {
+                x: TITLE_SCORE_TABLE_X + 50.,
+                y,
+            };
+            ui::draw_text(font, &label, text_pos, FRAME_COLOR);
+            y += SCORE_ROW_HEIGHT;
+        }
+    }
+}
+
+/// The options screen, where the player binds the gameplay actions to
+/// other keys
+#[derive(Default)]
+pub struct OptionsScene {
+    selected: usize,
+    // waiting for the new key of the selected action
+    capturing: bool,
+}
+
+impl Scene<Context> for OptionsScene {
+    fn handle_input(&mut self, ctx: &mut Context, input: &PlayerInput) -> Transition<Context> {
+        if self.capturing {
+            // the back key cancels, any other key that has a name is taken
+            if input.back {
+                self.capturing = false;
+            } else if let Some(key) = input.key.filter(|key| key_name(*key).is_some()) {
+                ctx.actions.rebind(REBINDABLE_ACTIONS[self.selected], key);
+                ctx.save_controls();
+                self.capturing = false;
+            }
+            return Transition::None;
+        }
+
+        let count = REBINDABLE_ACTIONS.len();
+        if input.nav_up {
+            self.selected = (self.selected + count - 1) % count;
+        }
+        if input.nav_down {
+            self.selected = (self.selected + 1) % count;
+        }
+        if input.confirm {
+            self.capturing = true;
+        }
+        match input.back {
+            true => Transition::Pop,
+            false => Transition::None,
+        }
+    }
+
+    fn draw(&self, ctx: &Context) {
+        const ROW_HEIGHT: f32 = 38.;
+        let font = ctx.assets.get_font();
+        let rect = LEADERBOARD_RECT;
+        unsafe {
+            DrawRectangleLinesEx(rect, FRAME_THICKNESS, FRAME_COLOR);
+        }
+        let mut y = rect.y + 20.;
+        ui::draw_text_centered_in(font, rect, y, "CONTROLS", FRAME_COLOR);
+        y += ROW_HEIGHT * 1.5;
+
+        for (index, action) in REBINDABLE_ACTIONS.into_iter().enumerate() {
+            let selected = index == self.selected;
+            let color = if selected { COLOR_WHITE } else { FRAME_COLOR };
+            let key = match selected && self.capturing {
+                true => "PRESS A KEY".to_string(),
+                false => ctx.actions.get_key_label(action),
+            };
+            let marker = if selected { ">" } else { " " };
+            let label = format!("{} {}", marker, action.get_label());
+            ui::draw_text(font, &label, Vector2 { x: rect.x + 30., y }, color);
+            ui::draw_text(
+                font,
+                &key,
+                Vector2 {
+                    x: rect.x + 330.,
+                    y,
+                },
+                color,
+            );
+            y += ROW_HEIGHT;
+        }
+
+        y += ROW_HEIGHT / 2.;
+        for line in ["GAMEPLAY TUNING IS READ", "FROM SPACEINV.TOML"] {
+            ui::draw_text_centered_in(font, rect, y, line, FRAME_COLOR);
+            y += ROW_HEIGHT;
+        }
+
+        let help = [
+            format!(
+                "{} TO CHANGE A KEY",
+                ctx.actions.get_key_label(Action::Confirm)
+            ),
+            format!("{} TO GO BACK", ctx.actions.get_key_label(Action::Back)),
+        ];
+        let mut y = rect.y + rect.height - 2. * ROW_HEIGHT - 10.;
+        for line in help {
+            ui::draw_text_centered_in(font, rect, y, &line, FRAME_COLOR);
+            y += ROW_HEIGHT;
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// Drawing helpers shared by the scenes
+
+use crate::constants::*;
+use crate::menu::Menu;
+use raylib_ffi::{
+    rl_str, Color, DrawRectangleGradientH, DrawRectangleLines, DrawText, DrawTextEx, Font,
+    MeasureText, MeasureTextEx, Rectangle, Vector2,
+};
+
+fn center_text_at(posx: i32, posy: i32, width: i32, text: &str) {
+    const YELLOW: Color = Color {
+        r: 243,
+        g: 216,
+        b: 63,
+        a: 255,
+    };
+
+    let text_width = unsafe { MeasureText(rl_str!(text), 34) };
+    let newx = posx + (width - text_width) / 2;
+    unsafe { DrawText(rl_str!(text), newx, posy, 34, YELLOW) };
+}
+
+pub fn draw_dialog_box(text1: &str, text2: &str, text3: &str, color: Color) {
+    const RWIDTH: i32 = 600;
+    const RHEIGHT: i32 = 200;
+    const RPOSX: i32 = (WORLD_WIDTH - RWIDTH) / 2;
+    const RPOSY: i32 = 100;
+    unsafe {
+        DrawRectangleGradientH(RPOSX, RPOSY, RWIDTH, RHEIGHT, color, color);
+        DrawRectangleLines(RPOSX, RPOSY, RWIDTH, RHEIGHT, color);
+    }
+    center_text_at(RPOSX, 150, RWIDTH, text1);
+    center_text_at(RPOSX, 190, RWIDTH, text2);
+    center_text_at(RPOSX, 230, RWIDTH, text3);
+}
+
+pub fn draw_text(font: Font, text: &str, pos: Vector2, color: Color) {
+    unsafe {
+        DrawTextEx(
+            font,
+            rl_str!(text),
+            pos,
+            FONT_SIZE as f32,
+            FONT_SPACING,
+            color,
+        );
+    }
+}
+
+/// Draws the menu items from `y` down, the selected one highlighted
+pub fn draw_menu(font: Font, rect: Rectangle, y: f32, menu: &Menu) {
+    const ITEM_HEIGHT: f32 = 45.;
+    let mut y = y;
+    for item in menu.get_items() {
+        let (text, color) = match *item == menu.get_selected() {
+            true => (format!("> {} <", item.get_label()), COLOR_WHITE),
+            false => (item.get_label().to_string(), FRAME_COLOR),
+        };
+        draw_text_centered_in(font, rect, y, &text, color);
+        y += ITEM_HEIGHT;
+    }
+}
+
+pub fn draw_text_centered_in(font: Font, rect: Rectangle, y: f32, text: &str, color: Color) {
+    let size = unsafe { MeasureTextEx(font, rl_str!(text), FONT_SIZE as f32, FONT_SPACING) };
+    let pos = Vector2 {
+        x: rect.x + (rect.width - size.x) / 2.,
+        y,
+    };
+    draw_text(font, text, pos, color);
+}
@@ -0,0 +1,325 @@
+// Alien waves described in data files, so new levels don't need a new build.
+//
+// Level N plays waves/levelN.toml when there is such a file, and the
+// classic formation (with the difficulty curve of the config) otherwise.
+// Only the grid is required:
+//
+//     # 1, 2 and 3 are ALIEN1, ALIEN2 and ALIEN3, '.' leaves a gap
+//     grid = [
+//         "3.3.3.3.3",
+//         "222222222",
+//         "1.1.1.1.1",
+//     ]
+//     offset_x = 120        # top left corner of the formation
+//     offset_y = 140
+//     step_size = 12        # pixels per step of the formation
+//     laser_interval = 0.3  # seconds between two alien shots
+//
+//     [mystery_ship]        # same keys as in spaceinv.toml, which gives
+//     scores = [300]        # the ones the wave doesn't set
+//
+// A wave file describes the whole level: the difficulty curve doesn't
+// apply on top of it.
+
+use crate::config::MysteryShipConfig;
+use crate::constants::*;
+use serde::Deserialize;
+use std::ops::Range;
+use std::path::Path;
+use toml::Spanned;
+
+pub const WAVES_DIR: &str = "waves";
+
+// The formation must end above this line, well clear of the obstacles
+const MAX_FORMATION_BOTTOM: usize =
+    ALIEN_OFFSET_Y + ALIEN_MAX_FORMATION_DROP + ALIEN_ROWS * ALIEN_SIZE;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Wave {
+    /// The alien type of every cell, row by row (None for a gap)
+    pub grid: Vec<Vec<Option<usize>>>,
+    pub offset_x: usize,
+    pub offset_y: usize,
+    // the config values are used for what the file doesn't set
+    pub step_size: Option<usize>,
+    pub laser_interval: Option<f64>,
+    pub mystery_ship: MysteryShipOverrides,
+}
+
+/// The mystery ship settings of a wave file, merged over the ones of the
+/// config
+#[derive(Clone, PartialEq, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MysteryShipOverrides {
+    pub speed: Option<f64>,
+    pub min_interval: Option<f64>,
+    pub max_interval: Option<f64>,
+    pub scores: Option<Vec<usize>>,
+    pub weights: Option<[u32; 4]>,
+    pub speed_factors: Option<[f64; 4]>,
+}
+
+impl MysteryShipOverrides {
+    /// `base` with the values the wave sets
+    pub fn apply_to(&self, base: &MysteryShipConfig) -> MysteryShipConfig {
+        MysteryShipConfig {
+            speed: self.speed.unwrap_or(base.speed),
+            min_interval: self.min_interval.unwrap_or(base.min_interval),
+            max_interval: self.max_interval.unwrap_or(base.max_interval),
+            scores: self.scores.clone().unwrap_or_else(|| base.scores.clone()),
+            weights: self.weights.unwrap_or(base.weights),
+            speed_factors: self.speed_factors.unwrap_or(base.speed_factors),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WaveFile {
+    grid: Spanned<Vec<Spanned<String>>>,
+    offset_x: Option<Spanned<usize>>,
+    offset_y: Option<Spanned<usize>>,
+    step_size: Option<Spanned<usize>>,
+    laser_interval: Option<Spanned<f64>>,
+    mystery_ship: Option<Spanned<MysteryShipOverrides>>,
+}
+
+// Line number (from 1) where a value starts
+fn line_of(text: &str, span: Range<usize>) -> usize {
+    text[..span.start].matches('\n').count() + 1
+}
+
+fn error_at(text: &str, span: Range<usize>, msg: String) -> String {
+    format!("line {}: {}", line_of(text, span), msg)
+}
+
+impl Wave {
+    /// The formation of the original game
+    pub fn classic() -> Self {
+        let grid = (0..ALIEN_ROWS)
+            .map(|row| {
+                let kind = match row {
+                    0 => ALIEN3,
+                    1 | 2 => ALIEN2,
+                    _ => ALIEN1,
+                };
+                vec![Some(kind); ALIEN_COLUMNS]
+            })
+            .collect();
+        Wave {
+            grid,
+            offset_x: ALIEN_OFFSET_X,
+            offset_y: ALIEN_OFFSET_Y,
+            step_size: None,
+            laser_interval: None,
+            mystery_ship: MysteryShipOverrides::default(),
+        }
+    }
+
+    /// Reads a wave file, for a config whose mystery ship settings are
+    /// `mystery_base` (the wave ones are checked merged over them)
+    pub fn parse(text: &str, mystery_base: &MysteryShipConfig) -> Result<Wave, String> {
+        let file: WaveFile = toml::from_str(text).map_err(|err| err.to_string())?;
+
+        let mut grid = Vec::new();
+        for row in file.grid.get_ref() {
+            let mut cells = Vec::new();
+            for c in row.get_ref().chars() {
+                cells.push(match c {
+                    '1' => Some(ALIEN1),
+                    '2' => Some(ALIEN2),
+                    '3' => Some(ALIEN3),
+                    '.' => None,
+                    _ => {
+                        return Err(error_at(
+                            text,
+                            row.span(),
+                            format!("unknown alien '{}' in the grid (use 1, 2, 3 or .)", c),
+                        ))
+                    }
+                });
+            }
+            grid.push(cells);
+        }
+        if !grid.iter().flatten().any(Option::is_some) {
+            return Err(error_at(
+                text,
+                file.grid.span(),
+                "the grid has no aliens".to_string(),
+            ));
+        }
+
+        let offset_x = file
+            .offset_x
+            .as_ref()
+            .map_or(ALIEN_OFFSET_X, |x| *x.get_ref());
+        let offset_y = file
+            .offset_y
+            .as_ref()
+            .map_or(ALIEN_OFFSET_Y, |y| *y.get_ref());
+        // report the offset when the file sets it, the grid otherwise
+        let span_of = |value: &Option<Spanned<usize>>| match value {
+            Some(value) => value.span(),
+            None => file.grid.span(),
+        };
+        let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+        let right = offset_x + width * ALIEN_SIZE;
+        if offset_x < OFFSETX as usize / 2 || right > (WINDOW_WIDTH - OFFSETX / 2) as usize {
+            return Err(error_at(
+                text,
+                span_of(&file.offset_x),
+                format!(
+                    "the formation doesn't fit the screen width ({} to {})",
+                    offset_x, right
+                ),
+            ));
+        }
+        let bottom = offset_y + grid.len() * ALIEN_SIZE;
+        if bottom > MAX_FORMATION_BOTTOM {
+            return Err(error_at(
+                text,
+                span_of(&file.offset_y),
+                format!(
+                    "the formation must end above {} (ends at {})",
+                    MAX_FORMATION_BOTTOM, bottom
+                ),
+            ));
+        }
+
+        if let Some(step_size) = &file.step_size {
+            if *step_size.get_ref() == 0 {
+                return Err(error_at(
+                    text,
+                    step_size.span(),
+                    "step_size must be greater than 0".to_string(),
+                ));
+            }
+        }
+        if let Some(interval) = &file.laser_interval {
+            if *interval.get_ref() <= 0. {
+                return Err(error_at(
+                    text,
+                    interval.span(),
+                    "laser_interval must be greater than 0".to_string(),
+                ));
+            }
+        }
+
+        if let Some(mystery) = &file.mystery_ship {
+            mystery
+                .get_ref()
+                .apply_to(mystery_base)
+                .validate()
+                .map_err(|msg| error_at(text, mystery.span(), msg))?;
+        }
+
+        Ok(Wave {
+            grid,
+            offset_x,
+            offset_y,
+            step_size: file.step_size.map(Spanned::into_inner),
+            laser_interval: file.laser_interval.map(Spanned::into_inner),
+            mystery_ship: file
+                .mystery_ship
+                .map(Spanned::into_inner)
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn load(path: &Path, mystery_base: &MysteryShipConfig) -> Result<Wave, String> {
+        let text =
+            std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Wave::parse(&text, mystery_base).map_err(|msg| format!("{}: {}", path.display(), msg))
+    }
+
+    /// Loads level1.toml, level2.toml... from `dir`, up to the first
+    /// missing one
+    pub fn load_dir(dir: &Path, mystery_base: &MysteryShipConfig) -> Result<Vec<Wave>, String> {
+        let mut waves = Vec::new();
+        loop {
+            let path = dir.join(format!("level{}.toml", waves.len() + 1));
+            if !path.exists() {
+                return Ok(waves);
+            }
+            waves.push(Wave::load(&path, mystery_base)?);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_wave_with_the_defaults() {
+        let base = MysteryShipConfig::default();
+        let wave = Wave::parse("grid = [\"3.3\", \"121\"]\nstep_size = 4\n", &base).unwrap();
+        assert_eq!(
+            wave.grid,
+            vec![
+                vec![Some(ALIEN3), None, Some(ALIEN3)],
+                vec![Some(ALIEN1), Some(ALIEN2), Some(ALIEN1)],
+            ]
+        );
+        assert_eq!(wave.offset_y, ALIEN_OFFSET_Y);
+        assert_eq!(wave.step_size, Some(4));
+        assert_eq!(wave.laser_interval, None);
+    }
+
+    #[test]
+    fn errors_point_to_the_offending_line() {
+        let base = MysteryShipConfig::default();
+        let err = Wave::parse("grid = [\n    \"111\",\n    \"1x1\",\n]\n", &base).unwrap_err();
+        assert_eq!(
+            err,
+            "line 3: unknown alien 'x' in the grid (use 1, 2, 3 or .)"
+        );
+
+        let err = Wave::parse("grid = [\"111\"]\n\noffset_y = 900\n", &base).unwrap_err();
+        assert!(
+            err.starts_with("line 3: the formation must end above"),
+            "{}",
+            err
+        );
+
+        let err = Wave::parse("grid = [\"...\"]\n", &base).unwrap_err();
+        assert_eq!(err, "line 1: the grid has no aliens");
+
+        let err = Wave::parse("grid = [\"1\"]\n[mystery_ship]\nspeed = 0.0\n", &base).unwrap_err();
+        assert!(err.starts_with("line 2: mystery_ship.speed"), "{}", err);
+
+        let err = Wave::parse("grid = [\"1\"]\nspeed = 3\n", &base).unwrap_err();
+        assert!(err.contains("line 2"), "{}", err);
+    }
+
+    #[test]
+    fn the_classic_wave_fits_its_own_limits() {
+        let wave = Wave::classic();
+        assert_eq!(wave.grid.len(), ALIEN_ROWS);
+        assert!(wave.offset_y + ALIEN_ROWS * ALIEN_SIZE <= MAX_FORMATION_BOTTOM);
+    }
+
+    #[test]
+    fn the_mystery_ship_keeps_what_the_wave_doesnt_set() {
+        let base = MysteryShipConfig {
+            speed: 5.,
+            min_interval: 10.,
+            max_interval: 20.,
+            ..MysteryShipConfig::default()
+        };
+        let wave = Wave::parse("grid = [\"1\"]\n[mystery_ship]\nscores = [300]\n", &base).unwrap();
+        let mystery = wave.mystery_ship.apply_to(&base);
+        assert_eq!(mystery.scores, vec![300]);
+        assert_eq!(mystery.speed, 5.);
+        assert_eq!(mystery.weights, base.weights);
+
+        // the merged settings are the ones checked
+        let err = Wave::parse(
+            "grid = [\"1\"]\n[mystery_ship]\nmax_interval = 8.0\n",
+            &base,
+        );
+        assert!(err
+            .unwrap_err()
+            .starts_with("line 2: mystery_ship.min_interval (10)"),);
+    }
+}
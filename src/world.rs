@@ -0,0 +1,1209 @@
+// The headless simulation core.
+//
+// The World owns the whole game state (ship, aliens, lasers, obstacles,
+// mystery ship, score and lives) and advances it one step at a time.
+// It never calls into raylib, so it can run without a window: the Game
+// feeds it the player input, renders its state and turns the events it
+// reports into sounds and state changes.
+
+use crate::alien::Alien;
+use crate::clock::TICK_DT;
+use crate::config::{Config, LevelConfig, MysteryShipConfig};
+use crate::constants::*;
+use crate::explosion::Explosion;
+use crate::laser::{Laser, LaserKind};
+use crate::mysteryship::{MysteryKind, MysteryShip};
+use crate::obstacle::Obstacle;
+use crate::popup::ScorePopup;
+use crate::powerup::{Effect, PowerUp, PowerUpKind};
+use crate::spaceship::{Spaceship, Weapon};
+use crate::wave::Wave;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use raylib_ffi::{Rectangle, Vector2};
+
+/// The player input for a single simulation step
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct TickInput {
+    pub left: bool,
+    pub right: bool,
+    pub fire: bool,
+}
+
+/// Something that happened during a simulation step
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WorldEvent {
+    LaserFired,
+    /// The alien formation started a new step: `note` (0 to 3) is the note
+    /// of the four-note heartbeat that goes with it
+    FormationStep {
+        note: usize,
+    },
+    AlienDestroyed,
+    MysteryShipDestroyed,
+    SpaceshipHit,
+    PowerUpCollected,
+    WaveCleared,
+    GameOver,
+}
+
+/// Same test as raylib's CheckCollisionRecs, without going through FFI
+pub fn check_collision_recs(rec1: Rectangle, rec2: Rectangle) -> bool {
+    rec1.x < rec2.x + rec2.width
+        && rec1.x + rec1.width > rec2.x
+        && rec1.y < rec2.y + rec2.height
+        && rec1.y + rec1.height > rec2.y
+}
+
+pub struct World {
+    pub spaceship: Spaceship,
+    pub lasers: Vec<Laser>,
+    pub obstacles: Vec<Obstacle>,
+    pub aliens: Vec<Alien>,
+    pub aliens_direction: i32,
+    // the formation moves one alien per tick: the next one to move, and
+    // whether the current step is a drop
+    march_index: usize,
+    march_drop: bool,
+    ticks_since_step: u32,
+    formation_steps: usize,
+    pub alien_lasers: Vec<Laser>,
+    pub explosions: Vec<Explosion>,
+    pub popups: Vec<ScorePopup>,
+    pub powerups: Vec<PowerUp>,
+    // the timed power-ups in use
+    pub effects: Vec<Effect>,
+    pub mysteryship: MysteryShip,
+    time: f64,
+    time_alien_last_fired: f64,
+    mysteryship_spawn_interval: f64,
+    time_mystery_last_bombed: f64,
+    time_last_spawned: f64,
+    pub lives: usize,
+    // shots fired in the game, they decide what the mystery ship is worth
+    shots_fired: usize,
+    // set when the aliens reached the ground, which ends the game whatever
+    // the lives left
+    pub invaded: bool,
+    pub level: usize,
+    pub score: usize,
+    // score and lives when the current level started
+    level_start_score: usize,
+    level_start_lives: usize,
+    events: Vec<WorldEvent>,
+    rng: StdRng,
+    config: Config,
+    // the waves loaded from files, for the first levels
+    waves: Vec<Wave>,
+}
+
+impl World {
+    /// Creates a new world that plays `waves` for its first levels (the
+    /// classic formation afterwards). All the gameplay randomness comes
+    /// from a single generator seeded with `seed`, so the same seed and the
+    /// same inputs always play out the same game.
+    pub fn new(seed: u64, config: &Config, waves: Vec<Wave>) -> Self {
+        let mut world = World {
+            spaceship: Spaceship::new(&config.player),
+            lasers: Vec::new(),
+            obstacles: Vec::new(),
+            aliens: Vec::new(),
+            aliens_direction: 1,
+            march_index: 0,
+            march_drop: false,
+            ticks_since_step: 0,
+            formation_steps: 0,
+            alien_lasers: Vec::new(),
+            explosions: Vec::new(),
+            popups: Vec::new(),
+            powerups: Vec::new(),
+            effects: Vec::new(),
+            mysteryship: MysteryShip::new(&config.mystery_ship),
+            time: 0.,
+            time_alien_last_fired: 0.,
+            mysteryship_spawn_interval: 0.,
+            time_mystery_last_bombed: 0.,
+            time_last_spawned: 0.,
+            lives: config.player.lives,
+            shots_fired: 0,
+            invaded: false,
+            level: 1,
+            score: 0,
+            level_start_score: 0,
+            level_start_lives: config.player.lives,
+            events: Vec::new(),
+            rng: StdRng::seed_from_u64(seed),
+            config: config.clone(),
+            waves,
+        };
+
+        world.create_obstacles();
+        world.create_aliens();
+        world.start_level();
+
+        world
+    }
+
+    /// Moves on to the next level, with a new wave of aliens
+    pub fn init_level(&mut self) {
+        self.level += 1;
+        self.reset();
+        self.start_level();
+    }
+
+    /// Plays the current level again from its start, with the score and
+    /// the lives the player had back then
+    pub fn restart_level(&mut self) {
+        self.score = self.level_start_score;
+        self.lives = self.level_start_lives;
+        self.lasers.clear();
+        self.effects.clear();
+        self.reset();
+        self.start_level();
+    }
+
+    fn start_level(&mut self) {
+        self.aliens_direction = 1;
+        self.march_index = 0;
+        self.march_drop = false;
+        self.mysteryship = MysteryShip::new(&self.get_mystery_config());
+        self.mysteryship_spawn_interval = self.next_spawn_interval();
+        self.time_last_spawned = self.time;
+        self.time_alien_last_fired = self.time;
+        self.level_start_score = self.score;
+        self.level_start_lives = self.lives;
+    }
+
+    /// Starts a brand new game from `seed`
+    pub fn init_game(&mut self, seed: u64) {
+        let waves = std::mem::take(&mut self.waves);
+        *self = World::new(seed, &self.config, waves);
+    }
+
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+
+    // The wave file of the current level, if there is one
+    fn get_wave(&self) -> Option<&Wave> {
+        self.waves.get(self.level - 1)
+    }
+
+    // The difficulty curve only applies to the levels without a wave file
+    fn get_difficulty(&self) -> LevelConfig {
+        match self.get_wave() {
+            Some(_) => LevelConfig::default(),
+            None => self.config.get_level(self.level).clone(),
+        }
+    }
+
+    // The wave settings of the mystery ship, over the config ones
+    fn get_mystery_config(&self) -> MysteryShipConfig {
+        match self.get_wave() {
+            Some(wave) => wave.mystery_ship.apply_to(&self.config.mystery_ship),
+            None => self.config.mystery_ship.clone(),
+        }
+    }
+
+    fn next_spawn_interval(&mut self) -> f64 {
+        let factor = self.get_difficulty().mystery_interval;
+        let mystery = self.get_mystery_config();
+        let range = mystery.min_interval * factor..mystery.max_interval * factor;
+        self.rng.gen_range(range)
+    }
+
+    pub fn reset(&mut self) {
+        self.spaceship.reset();
+        self.aliens.clear();
+        self.alien_lasers.clear();
+        self.explosions.clear();
+        self.popups.clear();
+        self.powerups.clear();
+        self.invaded = false;
+        self.obstacles.clear();
+        self.create_obstacles();
+        self.create_aliens();
+    }
+
+    pub fn create_obstacles(&mut self) {
+        // create the obstacles
+        const GAP: usize =
+            (WORLD_WIDTH as usize - (NUM_OBSTACLES * OBSTACLE_WIDTH)) / (NUM_OBSTACLES + 1);
+        for i in 0..NUM_OBSTACLES {
+            let offset_x = (i + 1) * GAP + i * OBSTACLE_WIDTH;
+            const OFFSET_Y: usize = WORLD_HEIGHT as usize - OBSTACLE_PADDING - OFFSETY as usize;
+            self.obstacles.push(Obstacle::new(offset_x, OFFSET_Y));
+        }
+    }
+
+    pub fn create_aliens(&mut self) {
+        let wave = self.get_wave().cloned().unwrap_or_else(Wave::classic);
+        // every level starts the formation a little lower
+        let offset_y = wave.offset_y + self.get_difficulty().formation_drop;
+        for (row, cells) in wave.grid.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                let Some(alien_type) = *cell else {
+                    continue;
+                };
+                let x = wave.offset_x + col * ALIEN_SIZE;
+                let y = offset_y + row * ALIEN_SIZE;
+                self.aliens.push(Alien::new(
+                    alien_type,
+                    col,
+                    Vector2 {
+                        x: x as f32,
+                        y: y as f32,
+                    },
+                    self.config.aliens.scores[alien_type],
+                ));
+            }
+        }
+    }
+
+    /// Advances the simulation by one fixed step and returns what happened
+    pub fn step(&mut self, input: &TickInput) -> Vec<WorldEvent> {
+        self.time += TICK_DT;
+        let now = self.time;
+
+        // Handle movement and laser fire
+        if input.left {
+            self.spaceship.move_left();
+        }
+        if input.right {
+            self.spaceship.move_right();
+        }
+        if input.fire {
+            let weapon = Weapon {
+                rapid_fire: self.has_effect(PowerUpKind::RapidFire),
+                double_shot: self.has_effect(PowerUpKind::DoubleShot),
+                piercing: self.has_effect(PowerUpKind::Piercing),
+            };
+            let lasers = self.spaceship.fire_lasers(now, weapon);
+            if !lasers.is_empty() {
+                self.lasers.extend(lasers);
+                self.shots_fired += 1;
+                self.events.push(WorldEvent::LaserFired);
+            }
+        }
+
+        // Update the spaceship (currently does nothing)
+        self.spaceship.update();
+
+        // Update all spaceship lasers
+        for laser in self.lasers.iter_mut() {
+            laser.update();
+        }
+
+        // Remove all inactive spaceship lasers
+        self.lasers.retain(|elem| elem.is_active());
+
+        // Remove all inactive blocks
+        for obstacle in self.obstacles.iter_mut() {
+            obstacle.remove_inactive_blocks();
+        }
+
+        // Remove all inactive aliens (the march goes on with the next one)
+        let removed_before = self.aliens[..self.march_index]
+            .iter()
+            .filter(|alien| !alien.is_active())
+            .count();
+        self.march_index -= removed_before;
+        self.aliens.retain(|elem| elem.is_active());
+        if self.aliens.is_empty() {
+            self.events.push(WorldEvent::WaveCleared);
+        }
+
+        // Update the aliens and what's left of the destroyed ones
+        self.move_aliens();
+        for explosion in self.explosions.iter_mut() {
+            explosion.update();
+        }
+        self.explosions.retain(|elem| elem.is_active());
+        for popup in self.popups.iter_mut() {
+            popup.update();
+        }
+        self.popups.retain(|elem| elem.is_active());
+
+        // Create alien lasers
+        self.aliens_shoot_laser(now);
+
+        // Update alien lasers
+        for laser in self.alien_lasers.iter_mut() {
+            laser.update();
+        }
+
+        // Remove all inactive alien lasers
+        self.alien_lasers.retain(|elem| elem.is_active());
+
+        // Update the mystery ship
+        if now - self.time_last_spawned > self.mysteryship_spawn_interval {
+            let weights = self.get_mystery_config().weights;
+            self.mysteryship.spawn(&mut self.rng, &weights);
+            self.time_last_spawned = now;
+            self.mysteryship_spawn_interval = self.next_spawn_interval();
+        }
+        self.mysteryship.update();
+        self.mystery_ship_drops_bomb(now);
+
+        // Update the falling power-ups and the ones in use
+        for powerup in self.powerups.iter_mut() {
+            powerup.update();
+        }
+        self.powerups.retain(|elem| elem.is_active());
+        for effect in self.effects.iter_mut() {
+            effect.ticks_left = effect.ticks_left.saturating_sub(1);
+        }
+        self.effects.retain(|effect| effect.ticks_left > 0);
+
+        if self.check_for_collisions() {
+            self.events.push(WorldEvent::GameOver);
+        }
+
+        std::mem::take(&mut self.events)
+    }
+
+    // Moves the formation like the arcade did: one alien per tick, so the
+    // fewer aliens are left, the faster the whole formation goes. Every
+    // step goes sideways, or down (turning around) once a side is reached.
+    pub fn move_aliens(&mut self) {
+        self.ticks_since_step += 1;
+        if self.aliens.is_empty() {
+            return;
+        }
+        if self.march_index >= self.aliens.len() {
+            self.march_index = 0;
+        }
+
+        let step_size = self
+            .get_wave()
+            .and_then(|wave| wave.step_size)
+            .unwrap_or(self.config.aliens.step_size);
+        let aliens = &self.config.aliens;
+        let dx = self.aliens_direction * step_size as i32;
+        if self.march_index == 0 {
+            if self.ticks_since_step < aliens.min_step_ticks {
+                return;
+            }
+            self.ticks_since_step = 0;
+            self.march_drop = self.aliens.iter().any(|alien| !alien.can_move_by(dx));
+            if self.march_drop {
+                self.aliens_direction = -self.aliens_direction;
+            }
+            self.events.push(WorldEvent::FormationStep {
+                note: self.formation_steps % 4,
+            });
+            self.formation_steps += 1;
+        }
+
+        let alien = &mut self.aliens[self.march_index];
+        if self.march_drop {
+            alien.move_down(aliens.down_distance);
+        } else {
+            alien.update(dx);
+        }
+        self.march_index = (self.march_index + 1) % self.aliens.len();
+    }
+
+    /// Most alien lasers allowed on screen at once in the current level
+    pub fn get_max_alien_lasers(&self) -> usize {
+        let max_lasers = &self.config.aliens.max_lasers;
+        max_lasers[(self.level - 1).min(max_lasers.len() - 1)]
+    }
+
+    /// Indexes of the aliens that can fire: the lowest one of each column
+    pub fn get_front_line(&self) -> Vec<usize> {
+        let mut front: Vec<usize> = Vec::new();
+        for (index, alien) in self.aliens.iter().enumerate() {
+            if !alien.is_active() {
+                continue;
+            }
+            let column = alien.get_column();
+            match front
+                .iter_mut()
+                .find(|other| self.aliens[**other].get_column() == column)
+            {
+                Some(other) => {
+                    if alien.get_rect().y > self.aliens[*other].get_rect().y {
+                        *other = index;
+                    }
+                }
+                None => front.push(index),
+            }
+        }
+        front
+    }
+
+    pub fn aliens_shoot_laser(&mut self, now: f64) {
+        let difficulty = self.get_difficulty();
+        let interval = self
+            .get_wave()
+            .and_then(|wave| wave.laser_interval)
+            .unwrap_or(self.config.aliens.laser_interval)
+            / difficulty.fire_rate;
+        let speed = (self.config.aliens.laser_speed * difficulty.laser_speed) as f32;
+        if now - self.time_alien_last_fired < interval
+            || self.alien_lasers.len() >= self.get_max_alien_lasers()
+        {
+            return;
+        }
+        let front = self.get_front_line();
+        if front.is_empty() {
+            return;
+        }
+        let aimed = self.rng.gen_bool(self.config.aliens.aimed_shot_chance);
+        let index = if aimed {
+            // the shooter closest to the column above the spaceship
+            let ship = self.spaceship.get_rect();
+            let ship_x = ship.x + ship.width / 2.;
+            let distance = |index: &usize| (self.aliens[*index].get_center().x - ship_x).abs();
+            *front
+                .iter()
+                .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+                .unwrap()
+        } else {
+            front[self.rng.gen_range(0..front.len())]
+        };
+        let alien = &self.aliens[index];
+        // the aimed shots are the fast ones
+        let kind = match aimed {
+            true => LaserKind::Rolling,
+            false => alien.get_laser_kind(),
+        };
+        self.alien_lasers
+            .push(Laser::alien(kind, alien.get_laser_position(), speed));
+        self.time_alien_last_fired = now;
+    }
+
+    pub fn has_effect(&self, kind: PowerUpKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    fn collect_powerup(&mut self, kind: PowerUpKind) {
+        self.events.push(WorldEvent::PowerUpCollected);
+        match kind {
+            PowerUpKind::ExtraLife => self.lives = (self.lives + 1).min(PLAYER_MAX_LIVES),
+            PowerUpKind::BunkerRepair => {
+                self.obstacles.clear();
+                self.create_obstacles();
+            }
+            PowerUpKind::RapidFire => self.start_effect(kind, 0),
+            PowerUpKind::DoubleShot => self.start_effect(kind, 1),
+            PowerUpKind::Piercing => self.start_effect(kind, 2),
+            PowerUpKind::Shield => self.start_effect(kind, 3),
+        }
+    }
+
+    // Starts a timed power-up, for the config duration at `index`: picking
+    // up one already in use starts it over
+    fn start_effect(&mut self, kind: PowerUpKind, index: usize) {
+        let seconds = self.config.powerups.durations[index];
+        self.effects.retain(|effect| effect.kind != kind);
+        self.effects.push(Effect::new(kind, seconds));
+    }
+
+    // The bomber drops its bombs when it flies over the spaceship
+    fn mystery_ship_drops_bomb(&mut self, now: f64) {
+        if !self.mysteryship.is_active()
+            || self.mysteryship.get_kind() != MysteryKind::Bomber
+            || now - self.time_mystery_last_bombed < MYSTERYSHIP_BOMB_INTERVAL
+        {
+            return;
+        }
+        let bomb_pos = self.mysteryship.get_bomb_position();
+        let ship = self.spaceship.get_rect();
+        if bomb_pos.x < ship.x || bomb_pos.x > ship.x + ship.width {
+            return;
+        }
+        let speed = self.config.aliens.laser_speed as f32;
+        self.alien_lasers
+            .push(Laser::alien(LaserKind::Bomb, bomb_pos, speed));
+        self.time_mystery_last_bombed = now;
+    }
+
+    /// Returns true when the game is over: the last life is lost, or an
+    /// alien reached the ground
+    fn check_for_collisions(&mut self) -> bool {
+        let scores = &self.get_mystery_config().scores;
+        let mystery_score = scores[self.shots_fired % scores.len()];
+        // ================
+        // spaceship lasers
+        // ================
+        for laser in self.lasers.iter_mut() {
+            // check against aliens
+            for alien in self.aliens.iter_mut() {
+                if alien.is_active() && check_collision_recs(alien.get_rect(), laser.get_rect()) {
+                    self.score += alien.get_score();
+                    alien.set_inactive();
+                    self.explosions.push(Explosion::new(alien.get_center()));
+                    if self.rng.gen_bool(self.config.powerups.drop_chance) {
+                        let kind = PowerUpKind::random(&mut self.rng);
+                        self.powerups.push(PowerUp::new(kind, alien.get_center()));
+                    }
+                    if laser.get_kind() != LaserKind::Piercing {
+                        laser.set_inactive();
+                    }
+                    self.events.push(WorldEvent::AlienDestroyed);
+                }
+            }
+            // check if obstacle is hit and damage it!
+            for obstacle in self.obstacles.iter_mut() {
+                for block in obstacle.blocks.iter_mut() {
+                    if check_collision_recs(block.get_rect(), laser.get_rect()) {
+                        block.set_inactive();
+                        laser.set_inactive();
+                    }
+                }
+            }
+            // check against mystery ship
+            if self.mysteryship.is_active()
+                && check_collision_recs(self.mysteryship.get_rect(), laser.get_rect())
+            {
+                let kind = self.mysteryship.get_kind();
+                let points = mystery_score * kind.get_score_factor();
+                self.score += points;
+                let rect = self.mysteryship.get_rect();
+                if kind == MysteryKind::Carrier {
+                    let center = Vector2 {
+                        x: rect.x + rect.width / 2.,
+                        y: rect.y + rect.height / 2.,
+                    };
+                    let kind = PowerUpKind::random(&mut self.rng);
+                    self.powerups.push(PowerUp::new(kind, center));
+                }
+                let position = Vector2 {
+                    x: rect.x,
+                    y: rect.y,
+                };
+                self.popups.push(ScorePopup::new(position, points));
+                self.mysteryship.set_inactive();
+                laser.set_inactive();
+                self.events.push(WorldEvent::MysteryShipDestroyed);
+            }
+            // check against alien lasers (yep, we can destroy alien lasers!)
+            for alien_laser in self.alien_lasers.iter_mut() {
+                if !laser.is_active()
+                    || !alien_laser.is_active()
+                    || !check_collision_recs(alien_laser.get_rect(), laser.get_rect())
+                {
+                    continue;
+                }
+                laser.set_inactive();
+                let chances = &self.config.aliens.laser_shoot_down_chances;
+                let chance = match alien_laser.get_kind().get_alien_index() {
+                    Some(index) => chances[index],
+                    None => 1.,
+                };
+                if self.rng.gen_bool(chance) {
+                    alien_laser.set_inactive();
+                    self.score += self.config.aliens.laser_bonus;
+                    self.explosions
+                        .push(Explosion::spark(alien_laser.get_center()));
+                }
+            }
+        }
+
+        // ================
+        // alien lasers
+        // ================
+        let mut game_over = false;
+        let shielded = self.has_effect(PowerUpKind::Shield);
+        for laser in self.alien_lasers.iter_mut() {
+            // already shot down
+            if !laser.is_active() {
+                continue;
+            }
+            // check if spaceship is hit
+            if check_collision_recs(laser.get_rect(), self.spaceship.get_rect()) {
+                laser.set_inactive();
+                if shielded {
+                    continue;
+                }
+                self.lives = self.lives.saturating_sub(1);
+                self.events.push(WorldEvent::SpaceshipHit);
+                if self.lives == 0 {
+                    game_over = true;
+                }
+            }
+            // check if obstacle is hit and damage it!
+            for obstacle in self.obstacles.iter_mut() {
+                for block in obstacle.blocks.iter_mut() {
+                    if check_collision_recs(block.get_rect(), laser.get_rect()) {
+                        block.set_inactive();
+                        laser.set_inactive();
+                    }
+                }
+            }
+        }
+
+        // =========
+        // power-ups
+        // =========
+        let mut collected = Vec::new();
+        for powerup in self.powerups.iter_mut() {
+            if powerup.is_active()
+                && check_collision_recs(powerup.get_rect(), self.spaceship.get_rect())
+            {
+                powerup.set_inactive();
+                collected.push(powerup.get_kind());
+            }
+        }
+        for kind in collected {
+            self.collect_powerup(kind);
+        }
+
+        // ===========
+        // alien ships
+        // ===========
+        for alien in self.aliens.iter().filter(|alien| alien.is_active()) {
+            // aliens chew through the obstacles on their way down
+            for obstacle in self.obstacles.iter_mut() {
+                for block in obstacle.blocks.iter_mut() {
+                    if check_collision_recs(block.get_rect(), alien.get_rect()) {
+                        block.set_inactive();
+                    }
+                }
+            }
+            // alien collision with ship, or alien landed
+            let rect = alien.get_rect();
+            if check_collision_recs(self.spaceship.get_rect(), rect)
+                || rect.y + rect.height >= ALIEN_GROUND_Y
+            {
+                self.invaded = true;
+                game_over = true;
+            }
+        }
+        game_over
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TICK_RATE;
+    use crate::config::LEVEL_CURVE;
+
+    fn alien_at(x: f32, y: f32) -> Alien {
+        let column = x as usize / ALIEN_SIZE;
+        Alien::new(ALIEN1, column, Vector2 { x, y }, ALIEN_SCORES[ALIEN1])
+    }
+
+    #[test]
+    fn aliens_reverse_and_drop_at_right_edge() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        let edge = (WINDOW_WIDTH - OFFSETX / 2) as f32 - ALIEN_SPRITE_SIZES[ALIEN1].x;
+        world.aliens = vec![alien_at(edge, 200.)];
+        world.ticks_since_step = ALIEN_MIN_STEP_TICKS;
+
+        world.move_aliens();
+
+        assert_eq!(world.aliens_direction, -1);
+        let rect = world.aliens[0].get_rect();
+        assert_eq!(rect.x, edge);
+        assert_eq!(rect.y, 200. + ALIEN_DOWN_DISTANCE as f32);
+    }
+
+    #[test]
+    fn aliens_reverse_and_drop_at_left_edge() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.aliens_direction = -1;
+        world.aliens = vec![alien_at((OFFSETX / 2) as f32 - 1., 200.)];
+        world.ticks_since_step = ALIEN_MIN_STEP_TICKS;
+
+        world.move_aliens();
+
+        assert_eq!(world.aliens_direction, 1);
+        assert_eq!(
+            world.aliens[0].get_rect().y,
+            200. + ALIEN_DOWN_DISTANCE as f32
+        );
+    }
+
+    #[test]
+    fn laser_destroys_alien_and_scores() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.aliens = vec![alien_at(300., 300.)];
+        world.lasers = vec![Laser::new(Vector2 { x: 310., y: 330. }, 0.)];
+
+        let events = world.step(&TickInput::default());
+
+        assert!(events.contains(&WorldEvent::AlienDestroyed));
+        assert_eq!(world.score, ALIEN_SCORES[ALIEN1]);
+    }
+
+    #[test]
+    fn a_destroyed_alien_leaves_a_short_lived_explosion() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.aliens = vec![alien_at(300., 300.), alien_at(100., 100.)];
+        world.lasers = vec![Laser::new(Vector2 { x: 310., y: 330. }, 0.)];
+
+        world.step(&TickInput::default());
+        assert_eq!(world.explosions.len(), 1);
+
+        for _ in 0..EXPLOSION_TICKS {
+            world.step(&TickInput::default());
+        }
+        assert!(world.explosions.is_empty());
+    }
+
+    #[test]
+    fn only_the_lowest_alien_of_a_column_fires() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.aliens = vec![
+            alien_at(110., 100.),
+            alien_at(110., 160.),
+            alien_at(220., 100.),
+        ];
+
+        assert_eq!(world.get_front_line(), vec![1, 2]);
+        world.aliens[1].set_inactive();
+        assert_eq!(world.get_front_line(), vec![0, 2]);
+    }
+
+    #[test]
+    fn aimed_shots_come_from_the_column_above_the_spaceship() {
+        let mut config = Config::default();
+        config.aliens.aimed_shot_chance = 1.;
+        let mut world = World::new(0, &config, Vec::new());
+        let ship = world.spaceship.get_rect();
+        let ship_x = ship.x + ship.width / 2.;
+        world.aliens = vec![alien_at(110., 100.), alien_at(ship_x - 19., 100.)];
+
+        world.aliens_shoot_laser(config.aliens.laser_interval);
+
+        let laser = world.alien_lasers[0].get_rect();
+        assert_eq!(
+            laser.x + laser.width / 2.,
+            world.aliens[1].get_laser_position().x
+        );
+        assert_eq!(laser.width, LaserKind::Rolling.get_size().x);
+    }
+
+    #[test]
+    fn alien_lasers_are_capped_by_level() {
+        let mut config = Config::default();
+        config.aliens.max_lasers = vec![1, 2];
+        let mut world = World::new(0, &config, Vec::new());
+        let interval = config.aliens.laser_interval;
+
+        for shot in 1..=3 {
+            world.aliens_shoot_laser(interval * shot as f64);
+        }
+        assert_eq!(world.alien_lasers.len(), 1);
+
+        world.level = 5;
+        for shot in 4..=6 {
+            world.aliens_shoot_laser(interval * shot as f64);
+        }
+        assert_eq!(world.alien_lasers.len(), 2);
+    }
+
+    #[test]
+    fn player_shots_destroy_alien_shots() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.lasers = vec![Laser::new(Vector2 { x: 310., y: 450. }, 0.)];
+        world.alien_lasers = vec![Laser::alien(
+            LaserKind::Plunger,
+            Vector2 { x: 312., y: 445. },
+            0.,
+        )];
+
+        world.step(&TickInput::default());
+
+        assert!(world.lasers.iter().all(|laser| !laser.is_active()));
+        assert!(world.alien_lasers.iter().all(|laser| !laser.is_active()));
+        assert_eq!(world.score, ALIEN_LASER_BONUS);
+        assert_eq!(world.explosions.len(), 1);
+    }
+
+    #[test]
+    fn an_alien_shot_can_survive_a_player_shot() {
+        let mut config = Config::default();
+        config.aliens.laser_shoot_down_chances = [0.; 3];
+        let mut world = World::new(0, &config, Vec::new());
+        world.lasers = vec![Laser::new(Vector2 { x: 310., y: 450. }, 0.)];
+        world.alien_lasers = vec![Laser::alien(
+            LaserKind::Rolling,
+            Vector2 { x: 312., y: 445. },
+            0.,
+        )];
+
+        world.step(&TickInput::default());
+
+        assert!(!world.lasers[0].is_active());
+        assert!(world.alien_lasers[0].is_active());
+        assert_eq!(world.score, 0);
+    }
+
+    #[test]
+    fn the_mystery_ship_score_depends_on_the_shots_fired() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.aliens.clear();
+        world.shots_fired = 8;
+        world.mysteryship.spawn(&mut world.rng, &[1, 0, 0, 0]);
+        let rect = world.mysteryship.get_rect();
+        world.lasers = vec![Laser::new(
+            Vector2 {
+                x: rect.x,
+                y: rect.y,
+            },
+            0.,
+        )];
+
+        world.step(&TickInput::default());
+
+        assert_eq!(world.score, MYSTERYSHIP_SCORES[8]);
+        assert_eq!(world.popups.len(), 1);
+    }
+
+    #[test]
+    fn the_bomber_bombs_the_spaceship_column() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.aliens.clear();
+        world.mysteryship.spawn(&mut world.rng, &[0, 1, 0, 0]);
+        let mut bombs_x = Vec::new();
+        for _ in 0..400 {
+            world.step(&TickInput::default());
+            for laser in world.alien_lasers.iter() {
+                let rect = laser.get_rect();
+                if rect.width == LaserKind::Bomb.get_size().x && !bombs_x.contains(&rect.x) {
+                    bombs_x.push(rect.x);
+                }
+            }
+        }
+
+        let ship = world.spaceship.get_rect();
+        assert!(!bombs_x.is_empty());
+        for x in bombs_x {
+            assert!(x >= ship.x - 9. && x <= ship.x + ship.width, "{}", x);
+        }
+    }
+
+    #[test]
+    fn the_carrier_drops_a_collectible() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.aliens.clear();
+        world.mysteryship.spawn(&mut world.rng, &[0, 0, 0, 1]);
+        let rect = world.mysteryship.get_rect();
+        world.lasers = vec![Laser::new(
+            Vector2 {
+                x: rect.x,
+                y: rect.y,
+            },
+            0.,
+        )];
+        world.step(&TickInput::default());
+        assert_eq!(world.powerups.len(), 1);
+
+        // right on the spaceship
+        let ship = world.spaceship.get_rect();
+        world.powerups = vec![PowerUp::new(
+            PowerUpKind::ExtraLife,
+            Vector2 {
+                x: ship.x + ship.width / 2.,
+                y: ship.y + ship.height / 2.,
+            },
+        )];
+        world.step(&TickInput::default());
+        assert_eq!(world.lives, PLAYER_LIVES + 1);
+    }
+
+    #[test]
+    fn extra_lives_stop_at_the_maximum() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        for _ in 0..PLAYER_MAX_LIVES + 2 {
+            collect(&mut world, PowerUpKind::ExtraLife);
+        }
+        assert_eq!(world.lives, PLAYER_MAX_LIVES);
+    }
+
+    #[test]
+    fn every_timed_powerup_lasts_its_own_duration() {
+        let mut config = Config::default();
+        config.powerups.durations = [1., 2., 3., 4.];
+        let mut world = World::new(0, &config, Vec::new());
+        collect(&mut world, PowerUpKind::Piercing);
+        collect(&mut world, PowerUpKind::Shield);
+        let seconds: Vec<u32> = world
+            .effects
+            .iter()
+            .map(|effect| effect.get_seconds_left())
+            .collect();
+        assert_eq!(seconds, vec![3, 4]);
+    }
+
+    // Drops a power-up right on the spaceship
+    fn collect(world: &mut World, kind: PowerUpKind) -> Vec<WorldEvent> {
+        let ship = world.spaceship.get_rect();
+        let center = Vector2 {
+            x: ship.x + ship.width / 2.,
+            y: ship.y + ship.height / 2.,
+        };
+        world.powerups = vec![PowerUp::new(kind, center)];
+        world.step(&TickInput::default())
+    }
+
+    #[test]
+    fn timed_powerups_change_the_gun_and_wear_off() {
+        let mut config = Config::default();
+        config.powerups.durations = [1.; 4];
+        let mut world = World::new(0, &config, Vec::new());
+        world.aliens.clear();
+        let events = collect(&mut world, PowerUpKind::DoubleShot);
+        assert!(events.contains(&WorldEvent::PowerUpCollected));
+        collect(&mut world, PowerUpKind::RapidFire);
+        // let the gun cool down
+        for _ in 0..20 {
+            world.step(&TickInput::default());
+        }
+
+        let fire = TickInput {
+            fire: true,
+            ..TickInput::default()
+        };
+        world.step(&fire);
+        assert_eq!(world.lasers.len(), 2);
+        // a rapid fire cooldown later
+        let ticks = (LASER_TIME / RAPID_FIRE_FACTOR / TICK_DT).ceil() as usize + 1;
+        for _ in 0..ticks {
+            world.step(&fire);
+        }
+        assert_eq!(world.lasers.len(), 4);
+
+        for _ in 0..TICK_RATE {
+            world.step(&TickInput::default());
+        }
+        assert!(world.effects.is_empty());
+    }
+
+    #[test]
+    fn piercing_lasers_go_through_the_aliens() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.aliens = vec![alien_at(300., 300.), alien_at(300., 360.)];
+        world.lasers = vec![Laser::piercing(Vector2 { x: 310., y: 400. }, -40.)];
+
+        world.step(&TickInput::default());
+        world.step(&TickInput::default());
+
+        assert!(world.aliens.iter().all(|alien| !alien.is_active()));
+    }
+
+    #[test]
+    fn the_shield_stops_alien_lasers() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        collect(&mut world, PowerUpKind::Shield);
+        let ship = world.spaceship.get_rect();
+        world.alien_lasers = vec![Laser::new(
+            Vector2 {
+                x: ship.x + 10.,
+                y: ship.y + 5.,
+            },
+            0.,
+        )];
+
+        let events = world.step(&TickInput::default());
+
+        assert!(!events.contains(&WorldEvent::SpaceshipHit));
+        assert_eq!(world.lives, PLAYER_LIVES);
+    }
+
+    #[test]
+    fn bunker_repair_rebuilds_the_obstacles() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        let blocks = world.obstacles[0].blocks.len();
+        world.obstacles[0].blocks.truncate(10);
+
+        collect(&mut world, PowerUpKind::BunkerRepair);
+
+        assert_eq!(world.obstacles[0].blocks.len(), blocks);
+    }
+
+    #[test]
+    fn same_seed_plays_the_same_game() {
+        let mut world1 = World::new(42, &Config::default(), Vec::new());
+        let mut world2 = World::new(42, &Config::default(), Vec::new());
+        let input = TickInput {
+            fire: true,
+            ..Default::default()
+        };
+
+        for tick in 0..600 {
+            assert_eq!(world1.step(&input), world2.step(&input), "tick {}", tick);
+        }
+
+        let lasers = |world: &World| -> Vec<(f32, f32)> {
+            world
+                .alien_lasers
+                .iter()
+                .map(|laser| (laser.get_rect().x, laser.get_rect().y))
+                .collect()
+        };
+        assert_eq!(lasers(&world1), lasers(&world2));
+        assert_eq!(world1.score, world2.score);
+        assert_eq!(world1.lives, world2.lives);
+    }
+
+    #[test]
+    fn mystery_ship_spawns_on_simulation_time() {
+        let mut world = World::new(7, &Config::default(), Vec::new());
+        world.aliens.clear();
+        world.obstacles.clear();
+
+        let mut ticks = 0;
+        while !world.mysteryship.is_active() {
+            world.step(&TickInput::default());
+            ticks += 1;
+        }
+
+        let elapsed = ticks as f64 * TICK_DT;
+        assert!(elapsed > MYSTERYSHIP_MIN_INTERVAL);
+        assert!(elapsed <= MYSTERYSHIP_MAX_INTERVAL + TICK_DT);
+    }
+
+    #[test]
+    fn losing_the_last_life_ends_the_game() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.lives = 1;
+        let ship = world.spaceship.get_rect();
+        world.alien_lasers = vec![Laser::new(
+            Vector2 {
+                x: ship.x,
+                y: ship.y,
+            },
+            0.,
+        )];
+
+        let events = world.step(&TickInput::default());
+
+        assert!(events.contains(&WorldEvent::SpaceshipHit));
+        assert!(events.contains(&WorldEvent::GameOver));
+        assert_eq!(world.lives, 0);
+    }
+
+    #[test]
+    fn aliens_reaching_the_ground_end_the_game() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        let height = ALIEN_SPRITE_SIZES[ALIEN1].y;
+        world.aliens = vec![alien_at(100., ALIEN_GROUND_Y - height - 1.)];
+        world.step(&TickInput::default());
+        assert!(!world.invaded);
+
+        world.aliens = vec![alien_at(100., ALIEN_GROUND_Y - height)];
+        let events = world.step(&TickInput::default());
+
+        assert!(events.contains(&WorldEvent::GameOver));
+        assert!(world.invaded);
+        assert_eq!(world.lives, PLAYER_LIVES);
+    }
+
+    #[test]
+    fn aliens_chew_through_the_obstacles() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        let block = world.obstacles[0].blocks[0].get_rect();
+        let blocks = world.obstacles[0].blocks.len();
+        world.aliens = vec![alien_at(block.x, block.y)];
+
+        world.step(&TickInput::default());
+        world.step(&TickInput::default());
+
+        assert!(world.obstacles[0].blocks.len() < blocks);
+    }
+
+    #[test]
+    fn every_level_starts_lower_and_fires_faster() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        let top = |world: &World| world.aliens[0].get_rect().y;
+        let first_top = top(&world);
+        world.init_level();
+        world.init_level();
+
+        let difficulty = &LEVEL_CURVE[2];
+        assert_eq!(top(&world), first_top + difficulty.formation_drop as f32);
+
+        // a shot is due sooner than the configured interval
+        world.alien_lasers.clear();
+        let now = world.time + ALIEN_LASER_INTERVAL / difficulty.fire_rate + 0.001;
+        world.aliens_shoot_laser(now);
+        assert_eq!(world.alien_lasers.len(), 1);
+    }
+
+    #[test]
+    fn wave_files_replace_the_classic_formation() {
+        let config = Config::default();
+        let wave = Wave::parse("grid = [\"3.3\"]\noffset_y = 200\n", &config.mystery_ship).unwrap();
+        let mut world = World::new(0, &config, vec![wave]);
+
+        assert_eq!(world.aliens.len(), 2);
+        assert_eq!(world.aliens[1].get_column(), 2);
+        assert_eq!(world.aliens[0].get_rect().y, 200.);
+
+        // the levels after the last wave file play the classic formation
+        world.init_level();
+        assert_eq!(world.aliens.len(), ALIEN_ROWS * ALIEN_COLUMNS);
+        world.init_game(1);
+        assert_eq!(world.aliens.len(), 2);
+    }
+
+    #[test]
+    fn restarting_a_level_restores_its_start() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.score = 1200;
+        world.init_level();
+
+        world.score = 1500;
+        world.lives = 1;
+        world.aliens.truncate(3);
+        world.restart_level();
+
+        assert_eq!(world.level, 2);
+        assert_eq!(world.score, 1200);
+        assert_eq!(world.lives, PLAYER_LIVES);
+        assert_eq!(world.aliens.len(), ALIEN_ROWS * ALIEN_COLUMNS);
+    }
+
+    #[test]
+    fn the_formation_moves_one_alien_per_tick() {
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        world.aliens = vec![alien_at(300., 200.), alien_at(400., 200.)];
+        world.ticks_since_step = ALIEN_MIN_STEP_TICKS;
+
+        world.move_aliens();
+        let x = |world: &World, i: usize| world.aliens[i].get_rect().x;
+        assert_eq!(x(&world, 0), 300. + ALIEN_STEP_SIZE as f32);
+        assert_eq!(x(&world, 1), 400.);
+
+        world.move_aliens();
+        assert_eq!(x(&world, 1), 400. + ALIEN_STEP_SIZE as f32);
+    }
+
+    #[test]
+    fn the_formation_speeds_up_as_aliens_die() {
+        let steps_in = |world: &mut World, ticks: usize| {
+            let mut steps = Vec::new();
+            for _ in 0..ticks {
+                world.move_aliens();
+                steps.extend(std::mem::take(&mut world.events));
+            }
+            steps
+        };
+
+        let mut world = World::new(0, &Config::default(), Vec::new());
+        let full = steps_in(&mut world, 240).len();
+        world.aliens.truncate(5);
+        let steps = steps_in(&mut world, 240);
+
+        assert!(steps.len() > full * 5, "{} vs {}", steps.len(), full);
+        // but never faster than the minimum step time
+        assert!(steps.len() <= 240 / ALIEN_MIN_STEP_TICKS as usize + 1);
+        // the heartbeat cycles through its four notes
+        let notes: Vec<usize> = steps
+            .iter()
+            .take(5)
+            .map(|event| match event {
+                WorldEvent::FormationStep { note } => *note,
+                _ => panic!("unexpected {:?}", event),
+            })
+            .collect();
+        assert_eq!(notes[4], notes[0]);
+        assert_eq!(notes[1], (notes[0] + 1) % 4);
+    }
+}